@@ -5,6 +5,7 @@ fn draw_data() -> DrawData<Vert> {
     DrawData::<Vert>::default()
 }
 
+#[allow(dead_code)]
 #[derive(Copy, Clone, Debug)]
 struct Vert {
     pos: [f32; 2],
@@ -53,8 +54,7 @@ fn rect(c: &mut Criterion) {
         b.iter(|| {
             draw.rect(
                 theme::YELLOW,
-                Vec2 { x: -1.0, y: 0.0 },
-                Vec2 { x: 1.0, y: 1.0 },
+                (Vec2 { x: -1.0, y: 0.0 }, Vec2 { x: 1.0, y: 1.0 }),
             );
         })
     });
@@ -66,8 +66,9 @@ fn rect_uv(c: &mut Criterion) {
         b.iter(|| {
             draw.rect_uv(
                 theme::YELLOW,
-                (Vec2 { x: -1.0, y: 0.0 }, Vec2 { x: 0.0, y: 0.0 }),
-                (Vec2 { x: 1.0, y: 1.0 }, Vec2 { x: 1.0, y: 1.0 }),
+                (Vec2 { x: -1.0, y: 0.0 }, Vec2 { x: 1.0, y: 1.0 }),
+                Vec2 { x: 0.0, y: 0.0 },
+                Vec2 { x: 1.0, y: 1.0 },
             )
         })
     });
@@ -2,8 +2,12 @@
 extern crate glium;
 extern crate immediate_mode;
 
-use glium::{glutin, Surface};
-use immediate_mode::{text::Texture, Color, Input, Renderer, Theme, Vec2, UI};
+use glium::{glutin, Surface, Texture2d};
+use immediate_mode::{
+    draw::{FillRule, Gradient, LineCap, LineJoin, Path, StrokeStyle, TextureId},
+    text::{AtlasUpdate, Font, FontFamily, Fonts, GlyphMode, Texture},
+    Color, Input, Key, Modifiers, MouseButton, Rect, Theme, Vec2, UI,
+};
 
 const VERT_SHADER_SRC: &str = r#"
 #version 140
@@ -32,8 +36,19 @@ in vec2 f_uv;
 
 out vec4 color;
 
+uniform sampler2D u_texture;
+uniform bool u_sdf;
+
 void main() {
-    color = f_color;
+    if (u_sdf) {
+        // threshold the distance field at its edge, smoothing over a pixel
+        float distance = texture(u_texture, f_uv).a;
+        float smoothing = fwidth(distance) * 0.5;
+        float alpha = smoothstep(0.5 - smoothing, 0.5 + smoothing, distance);
+        color = vec4(f_color.rgb, f_color.a * alpha);
+    } else {
+        color = f_color * texture(u_texture, f_uv);
+    }
 }
 "#;
 
@@ -60,68 +75,75 @@ implement_vertex!(Vert, pos, uv, color);
 
 use std::collections::*;
 
-fn load_font(font_data: &[u8]) -> (immediate_mode::text::Texture, HashMap<char, (Vec2, Vec2)>) {
-    use immediate_mode::text::{point, Font, Scale};
-
-    // This only succeeds if collection consists of one font
-    let font = Font::from_bytes(font_data).expect("Error constructing Font");
-    // The font size to use
-    let scale = Scale::uniform(20.0 * 2.0);
-    let v_metrics = font.v_metrics(scale);
-    let ascent = v_metrics.ascent as u32;
+/// Upload a texture to the GPU
+fn upload(display: &glium::Display, rgba: Vec<u8>, (width, height): (u32, u32)) -> Texture2d {
+    let image = glium::texture::RawImage2d::from_raw_rgba(rgba, (width, height));
+    Texture2d::new(display, image).unwrap()
+}
 
-    let mut texture = Texture::new(1024, 1024);
+/// Expand a grayscale coverage texture into white RGBA with coverage as alpha
+fn coverage_to_rgba(texture: &Texture) -> Vec<u8> {
+    texture
+        .pixels()
+        .iter()
+        .flat_map(|&a| vec![255, 255, 255, a])
+        .collect()
+}
 
-    for y in 0..(v_metrics.ascent - v_metrics.descent).ceil() as u32 {
-        texture[(0, y)] = 255;
+/// Expand part of a coverage texture, given in texels, into white RGBA
+fn region_to_rgba(texture: &Texture, region: Rect) -> Vec<u8> {
+    let (x0, y0) = (region.min.x as u32, region.min.y as u32);
+    let (x1, y1) = (region.max.x as u32, region.max.y as u32);
+    let mut rgba = Vec::with_capacity(((x1 - x0) * (y1 - y0) * 4) as usize);
+    for y in y0..y1 {
+        for x in x0..x1 {
+            rgba.extend(&[255, 255, 255, texture[(x, y)]]);
+        }
     }
+    rgba
+}
 
-    let mut cursor: (i32, i32) = (4, ascent as i32);
-    let mut atlas = HashMap::with_capacity(128 - 32);
-
-    // Loop through the glyphs in the text, positing each one on a line
-    for c in (32u8..128)
-        .map(|c| c as char)
-        .chain("žБВГИІЇЙЈКЛЉМНЊОПРСТЋУЎ".chars())
-    {
-        let glyph = font.glyph(c).scaled(scale).positioned(point(0.0, 0.0));
-        if let Some(bb) = glyph.pixel_bounding_box() {
-            let y_min = bb.min.y;
-            let x_min = bb.min.x;
-            glyph.draw(|x, y, v| {
-                texture[(x + (cursor.0 + x_min) as u32, y + (cursor.1 + y_min) as u32)] =
-                    (v * 255.0) as u8;
-            });
-            atlas.insert(
-                c,
-                (
-                    Vec2::new(
-                        (cursor.0 + bb.min.x) as f32 / 1024.0,
-                        (cursor.1 + bb.min.y) as f32 / 1024.0,
-                    ),
-                    Vec2::new(
-                        (cursor.0 + bb.max.x) as f32 / 1024.0,
-                        (cursor.1 + bb.max.y) as f32 / 1024.0,
-                    ),
-                ),
-            );
-            cursor.0 += bb.max.x + 1;
-            if cursor.0 > 1024 - 16 {
-                cursor.0 = 0;
-                cursor.1 += (v_metrics.ascent - v_metrics.descent).ceil() as i32;
-            }
+/// Checkerboard image to show a second texture next to the font atlas
+fn checkerboard(size: u32) -> Vec<u8> {
+    let mut rgba = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let c = if (x / 8 + y / 8) % 2 == 0 { 255 } else { 64 };
+            rgba.extend(&[c, c, c, 255]);
         }
     }
+    rgba
+}
 
-    // display font
-    // for uvs in atlas.values() {
-    // draw.rect_uv(
-    // Theme::DARK.fg_disabled,
-    // (uvs.0 * 1024.0, uvs.0),
-    // (uvs.1 * 1024.0, uvs.1),
-    // );
-    // }
-    (texture, atlas)
+const CHECKERBOARD: TextureId = TextureId::new(1);
+
+fn map_key(key: glutin::event::VirtualKeyCode) -> Option<Key> {
+    use glutin::event::VirtualKeyCode as K;
+
+    Some(match key {
+        K::Tab => Key::Tab,
+        K::Left => Key::Left,
+        K::Right => Key::Right,
+        K::Up => Key::Up,
+        K::Down => Key::Down,
+        K::PageUp => Key::PageUp,
+        K::PageDown => Key::PageDown,
+        K::Home => Key::Home,
+        K::End => Key::End,
+        K::Insert => Key::Insert,
+        K::Delete => Key::Delete,
+        K::Back => Key::Backspace,
+        K::Space => Key::Space,
+        K::Return => Key::Enter,
+        K::Escape => Key::Escape,
+        K::A => Key::A,
+        K::C => Key::C,
+        K::V => Key::V,
+        K::X => Key::X,
+        K::Y => Key::Y,
+        K::Z => Key::Z,
+        _ => return None,
+    })
 }
 
 fn main() {
@@ -135,9 +157,46 @@ fn main() {
     let program =
         glium::Program::from_source(&display, VERT_SHADER_SRC, FRAG_SHADER_SRC, None).unwrap();
 
+    // the font atlas is the default texture, it has a white texel at the
+    // opaque uv for drawing solid shapes
+    let sans =
+        Font::from_bytes(&include_bytes!("../../fonts/Source/SourceSansPro-Regular.ttf")[..])
+            .expect("Error constructing Font");
+    let code =
+        Font::from_bytes(&include_bytes!("../../fonts/Source/SourceCodePro-Regular.ttf")[..])
+            .expect("Error constructing Font");
+    let mut font_texture = upload(&display, vec![0; 4], (1, 1));
+    let checker_texture = upload(&display, checkerboard(64), (64, 64));
+
     let mut cursor_pos = glutin::dpi::PhysicalPosition::new(0.0, 0.0);
-    let mut cursor_down = false;
+    let mut buttons: HashMap<MouseButton, bool> = HashMap::new();
+    let mut scroll_lines = Vec2::zero();
+    let mut scroll_pixels = Vec2::zero();
+    let mut keys: Vec<(Key, bool)> = Vec::new();
+    let mut text = String::new();
+    let mut modifiers = Modifiers::default();
     let mut ui: UI<Vert> = UI::new(Input::new(None, false));
+    // set IM_SDF to render text from a distance field
+    let sdf = std::env::var_os("IM_SDF").is_some();
+    let mode = if sdf {
+        GlyphMode::Sdf {
+            size: 32.0,
+            spread: 4.0,
+        }
+    } else {
+        GlyphMode::Coverage
+    };
+    let mut fonts = Fonts::new(1024, 1024);
+    let sans = fonts.add_font(sans, mode);
+    let code = fonts.add_font(code, mode);
+    fonts.push_font(FontFamily::PROPORTIONAL, sans);
+    fonts.push_font(FontFamily::MONOSPACE, code);
+    // either font fills in glyphs the other is missing
+    fonts.add_fallback(sans);
+    fonts.add_fallback(code);
+    ui.set_fonts(fonts);
+    // feather edges since the window is not multisampled
+    ui.draw(|data| data.set_anti_alias(true));
     event_loop.run(move |event, _, control_flow| {
         use glutin::event::{Event, StartCause, WindowEvent};
         use glutin::event_loop::ControlFlow;
@@ -156,11 +215,51 @@ fn main() {
                     cursor_pos = position;
                 }
                 WindowEvent::MouseInput { state, button, .. } => {
-                    use glutin::event::{ElementState, MouseButton};
+                    use glutin::event::{ElementState, MouseButton as B};
+
+                    let button = match button {
+                        B::Left => MouseButton::Left,
+                        B::Right => MouseButton::Right,
+                        B::Middle => MouseButton::Middle,
+                        B::Other(0) => MouseButton::Extra1,
+                        B::Other(1) => MouseButton::Extra2,
+                        B::Other(_) => return,
+                    };
+                    buttons.insert(button, state == ElementState::Pressed);
+                }
+                WindowEvent::MouseWheel { delta, .. } => {
+                    use glutin::event::MouseScrollDelta;
+
+                    match delta {
+                        MouseScrollDelta::LineDelta(x, y) => {
+                            scroll_lines = scroll_lines + Vec2::new(x, y);
+                        }
+                        MouseScrollDelta::PixelDelta(p) => {
+                            scroll_pixels = scroll_pixels + Vec2::new(p.x as f32, p.y as f32);
+                        }
+                    }
+                    return;
+                }
+                WindowEvent::KeyboardInput { input, .. } => {
+                    use glutin::event::ElementState;
 
-                    if button == MouseButton::Left {
-                        cursor_down = state == ElementState::Pressed;
+                    if let Some(key) = input.virtual_keycode.and_then(map_key) {
+                        keys.push((key, input.state == ElementState::Pressed));
                     }
+                    return;
+                }
+                WindowEvent::ReceivedCharacter(c) => {
+                    text.push(c);
+                    return;
+                }
+                WindowEvent::ModifiersChanged(state) => {
+                    modifiers = Modifiers {
+                        shift: state.shift(),
+                        ctrl: state.ctrl(),
+                        alt: state.alt(),
+                        logo: state.logo(),
+                    };
+                    return;
                 }
                 _ => return,
             },
@@ -173,10 +272,33 @@ fn main() {
             _ => return,
         }
 
-        ui.next_frame(Input::new(
+        let mut input = Input::new(
             Some(Vec2::new(cursor_pos.x as f32, cursor_pos.y as f32)),
-            cursor_down,
-        ));
+            false,
+        );
+        for (&button, &down) in buttons.iter() {
+            input.mouse_button(button, down);
+        }
+        input.scroll_lines(scroll_lines);
+        input.scroll_pixels(scroll_pixels);
+        scroll_lines = Vec2::zero();
+        scroll_pixels = Vec2::zero();
+        for (key, pressed) in keys.drain(..) {
+            input.key_event(key, pressed);
+        }
+        for c in text.drain(..) {
+            input.text_event(c);
+        }
+        input.set_modifiers(modifiers);
+        ui.next_frame(input);
+
+        if ui.key_pressed(Key::Escape) {
+            *control_flow = ControlFlow::Exit;
+            return;
+        }
+        if !ui.text_input().is_empty() {
+            println!("{:#x} TYPED   {:?}", frame, ui.text_input());
+        }
 
         frame += 1;
 
@@ -186,23 +308,19 @@ fn main() {
         ui.draw(|data| {
             data.rect(
                 Theme::DARK.bg,
-                Vec2::new(0.0, 0.0),
-                Vec2::new(width as f32, height as f32),
+                (Vec2::new(0.0, 0.0), Vec2::new(width as f32, height as f32)),
             )
         });
 
         use immediate_mode as im;
 
-        fn draw_button<V>(ui: &mut UI<V>, color: Color, pos: Vec2) -> (Vec2, Vec2)
+        fn draw_button<V>(ui: &mut UI<V>, color: Color, region: Rect)
         where
             V: From<im::draw::Vert> + Copy,
         {
-            let size = Vec2 { x: 100.0, y: 20.0 };
             ui.draw(|data| {
-                data.rect(color, pos, pos + size);
+                data.rect_rounded(color, region, 4.0);
             });
-
-            (pos, pos + size)
         }
 
         fn button<S: AsRef<str>, V>(ui: &mut UI<V>, label: &S, pos: Vec2) -> im::Event
@@ -210,6 +328,16 @@ fn main() {
             V: From<im::draw::Vert> + Copy,
         {
             let id = ui.calculate_id(label.as_ref());
+            // size the button to fit its label
+            let label_size = ui.measure_text(FontFamily::PROPORTIONAL, 16.0, label.as_ref());
+            let region = Rect::from_origin_size(pos, label_size + 8.0);
+            ui.set_focusable(id);
+
+            if ui.has_focus(id) {
+                ui.draw(|data| {
+                    data.rect_rounded_outline(Theme::DARK.focus, region.expand(2.0), 5.0, 2.0)
+                });
+            }
 
             let color = if ui.is_held(id) {
                 Theme::DARK.active
@@ -221,7 +349,14 @@ fn main() {
                 }
             };
 
-            let region = draw_button(ui, color, pos);
+            draw_button(ui, color, region);
+            ui.text(
+                FontFamily::PROPORTIONAL,
+                16.0,
+                pos + 4.0,
+                Theme::DARK.fg,
+                label.as_ref(),
+            );
             ui.event(id, region)
         }
 
@@ -230,37 +365,206 @@ fn main() {
                 .on_hover(|_| println!("{:#x} HOVERED 1", frame))
                 .on_hold(|_| println!("{:#x} HELD    1", frame))
                 .on_click(|_| println!("{:#x} CLICKED 1", frame))
-                .tooltip(ui, &"Hello");
+                .on_right_click(|_| println!("{:#x} RIGHT   1", frame))
+                .on_double_click(|_| println!("{:#x} DOUBLE  1", frame))
+                .tooltip(ui, "Hello");
         });
 
         button(&mut ui, &"Hello", Vec2::new(10.0, 100.0))
             .on_hover(|_| println!("{:#x} HOVERED 2", frame))
             .on_hold(|_| println!("{:#x} HELD    2", frame))
-            .on_click(|_| println!("{:#x} CLICKED 2", frame));
+            .on_click(|_| println!("{:#x} CLICKED 2", frame))
+            .on_drag(|delta| println!("{:#x} DRAGGED 2 {:?}", frame, delta));
 
-        let renderer = ui.finish_frame();
+        // clip a wide rectangle to a smaller region
+        ui.push_clip_rect((Vec2::new(10.0, 150.0), Vec2::new(110.0, 170.0)));
+        ui.draw(|data| {
+            data.rect(
+                Theme::DARK.selected,
+                (Vec2::new(0.0, 155.0), Vec2::new(200.0, 165.0)),
+            )
+        });
+        ui.pop_clip_rect();
 
-        let draw_params = glium::DrawParameters {
-            blend: glium::Blend::alpha_blending(),
+        // draw from a second texture in the same frame
+        ui.draw(|data| {
+            data.image(
+                CHECKERBOARD,
+                (Vec2::new(10.0, 200.0), Vec2::new(74.0, 264.0)),
+                Vec2::new(0.0, 0.0),
+                Vec2::new(1.0, 1.0),
+                Color(0xFF_FF_FF_FF),
+            )
+        });
+
+        // a knob and a spinner turning with time
+        let spin = ui.time() as f32 * 4.0;
+        ui.draw(|data| {
+            let center = Vec2::new(300.0, 232.0);
+            data.circle(Theme::DARK.element, center, 24.0);
+            data.circle_outline(Theme::DARK.fg, center, 24.0, 2.0);
+            let round = StrokeStyle {
+                cap: LineCap::Round,
+                ..StrokeStyle::from(3.0)
+            };
+            data.arc_outline(Theme::DARK.focus, center, 30.0, spin, spin + 2.0, round);
+            let shine = Gradient::radial(
+                Vec2::new(362.0, 226.0),
+                28.0,
+                Theme::DARK.active,
+                Theme::DARK.hover,
+            );
+            data.rect_rounded_gradient(
+                (Vec2::new(346.0, 220.0), Vec2::new(394.0, 244.0)),
+                12.0,
+                &shine,
+            );
+        });
+
+        // a noisy plot which doubles back sharply without spiking
+        ui.draw(|data| {
+            let points: Vec<_> = (0..24)
+                .map(|i| {
+                    let noise = ((i * 7919) % 13) as f32 - 6.0;
+                    Vec2::new(420.0 + i as f32 * 6.0, 232.0 + noise * 3.0)
+                })
+                .collect();
+            let style = StrokeStyle {
+                join: LineJoin::Round,
+                ..StrokeStyle::from(2.0)
+            };
+            data.polyline(Theme::DARK.fg, style, &points);
+
+            // a hexagon icon
+            let hexagon: Vec<_> = (0..6)
+                .map(|i| {
+                    let (sin, cos) = (i as f32 * std::f32::consts::PI / 3.0).sin_cos();
+                    Vec2::new(590.0 + 16.0 * cos, 232.0 + 16.0 * sin)
+                })
+                .collect();
+            data.polygon_outline(Theme::DARK.focus, 2.0, &hexagon);
+
+            // a concave star
+            let star: Vec<_> = (0..10)
+                .map(|i| {
+                    let radius = if i % 2 == 0 { 16.0 } else { 7.0 };
+                    let (sin, cos) = (i as f32 * std::f32::consts::PI / 5.0).sin_cos();
+                    Vec2::new(640.0 + radius * sin, 232.0 - radius * cos)
+                })
+                .collect();
+            data.polygon_fill(Theme::DARK.hover, &star);
+
+            // a wire between two nodes
+            let (from, to) = (Vec2::new(420.0, 280.0), Vec2::new(640.0, 320.0));
+            let bend = Vec2::new(80.0, 0.0);
+            data.bezier_cubic(Theme::DARK.focus, 2.0, from, from + bend, to - bend, to);
+
+            // a heart icon built from curves
+            let (x, y) = (680.0, 220.0);
+            let heart = Path::new()
+                .move_to(Vec2::new(x, y + 6.0))
+                .cubic_to(
+                    Vec2::new(x, y - 4.0),
+                    Vec2::new(x + 16.0, y - 4.0),
+                    Vec2::new(x + 16.0, y + 8.0),
+                )
+                .line_to(Vec2::new(x, y + 24.0))
+                .line_to(Vec2::new(x - 16.0, y + 8.0))
+                .cubic_to(
+                    Vec2::new(x - 16.0, y - 4.0),
+                    Vec2::new(x, y - 4.0),
+                    Vec2::new(x, y + 6.0),
+                )
+                .close();
+            data.path_fill(im::theme::dark::RED, &heart, FillRule::NonZero);
+            data.path_stroke(Theme::DARK.fg, 1.5, &heart);
+        });
+
+        // wrapped text truncated to the height of its box
+        let text_box = Rect::from_origin_size(Vec2::new(100.0, 200.0), Vec2::new(160.0, 64.0));
+        let ants = ui.time() as f32 * 16.0;
+        ui.draw(|data| {
+            let theme = &Theme::DARK;
+            data.shadow(
+                text_box,
+                0.0,
+                theme.shadow_size,
+                theme.shadow_offset,
+                theme.shadow,
+            );
+            data.rect(theme.bg_child, text_box);
+            let marquee = StrokeStyle {
+                dash: &[4.0, 4.0],
+                dash_phase: ants,
+                ..StrokeStyle::from(1.0)
+            };
+            data.rect_rounded_outline(Theme::DARK.fg, text_box, 0.0, marquee);
+        });
+        let options = im::text::TextOptions {
+            h_align: im::text::HAlign::Center,
+            ellipsis: true,
             ..Default::default()
         };
+        ui.text_block(
+            FontFamily::PROPORTIONAL,
+            &options,
+            text_box,
+            Theme::DARK.fg,
+            "Text blocks wrap between words, align within their box and end with an ellipsis when they run out of room.",
+        );
 
-        let uniforms = uniform!(
-            u_view: {
-                let origin = (0.0, 0.0);
-                let l = origin.0; // left
-                let r = origin.0 + width as f32 / scale_factor as f32;
-                let t = origin.1; // top
-                let b = origin.1 + height as f32 / scale_factor as f32;
-                [
-                    [2.0 / (r - l), 0.0, 0.0, 0.0],
-                    [0.0, 2.0 / (t - b), 0.0, 0.0],
-                    [0.0, 0.0, -1.0, 0.0],
-                    [(r + l) / (l - r), (t + b) / (b - t), 0.0, 1.0],
-                ]
-            }
+        ui.text(
+            FontFamily::MONOSPACE,
+            16.0,
+            Vec2::new(10.0, 280.0),
+            Theme::DARK.fg,
+            &format!("frame {:#x}", frame),
         );
 
+        // upload glyphs rasterized this frame
+        if let Some(atlas) = ui.fonts_mut().map(Fonts::atlas_mut) {
+            let update = atlas.take_update();
+            let texture = atlas.texture();
+            match update {
+                Some(AtlasUpdate::Full) => {
+                    font_texture =
+                        upload(&display, coverage_to_rgba(texture), texture.dimensions());
+                }
+                Some(AtlasUpdate::Partial(regions)) => {
+                    for region in regions {
+                        let image = glium::texture::RawImage2d::from_raw_rgba(
+                            region_to_rgba(texture, region),
+                            (region.width() as u32, region.height() as u32),
+                        );
+                        let rect = glium::Rect {
+                            left: region.min.x as u32,
+                            bottom: region.min.y as u32,
+                            width: region.width() as u32,
+                            height: region.height() as u32,
+                        };
+                        font_texture.write(rect, image);
+                    }
+                }
+                None => (),
+            }
+        }
+
+        let renderer = ui.finish_frame();
+
+        let u_view = {
+            let origin = (0.0, 0.0);
+            let l = origin.0; // left
+            let r = origin.0 + width as f32 / scale_factor as f32;
+            let t = origin.1; // top
+            let b = origin.1 + height as f32 / scale_factor as f32;
+            [
+                [2.0 / (r - l), 0.0, 0.0, 0.0],
+                [0.0, 2.0 / (t - b), 0.0, 0.0],
+                [0.0, 0.0, -1.0, 0.0],
+                [(r + l) / (l - r), (t + b) / (b - t), 0.0, 1.0],
+            ]
+        };
+
         let vbo = glium::VertexBuffer::new(&display, renderer.verts()).unwrap();
         let ibo = glium::IndexBuffer::new(
             &display,
@@ -271,9 +575,42 @@ fn main() {
 
         let mut target = display.draw();
         target.clear_color(0.0, 0.0, 0.0, 0.0);
-        target
-            .draw(&vbo, &ibo, &program, &uniforms, &draw_params)
-            .unwrap();
+        for command in renderer.commands() {
+            // scissor rectangles are in physical pixels from the bottom left
+            let scissor = command.clip_rect.map(|clip| {
+                let scale = scale_factor as f32;
+                glium::Rect {
+                    left: (clip.min.x * scale) as u32,
+                    bottom: (height as f32 - clip.max.y * scale).max(0.0) as u32,
+                    width: (clip.width() * scale) as u32,
+                    height: (clip.height() * scale) as u32,
+                }
+            });
+            let draw_params = glium::DrawParameters {
+                blend: glium::Blend::alpha_blending(),
+                scissor,
+                ..Default::default()
+            };
+
+            let texture = if command.texture == CHECKERBOARD {
+                &checker_texture
+            } else {
+                &font_texture
+            };
+            let u_sdf = sdf && command.texture == TextureId::DEFAULT;
+            let uniforms = uniform!(u_view: u_view, u_texture: texture, u_sdf: u_sdf);
+
+            let range = command.first_index..command.first_index + command.index_count;
+            target
+                .draw(
+                    &vbo,
+                    ibo.slice(range).unwrap(),
+                    &program,
+                    &uniforms,
+                    &draw_params,
+                )
+                .unwrap();
+        }
 
         target.finish().unwrap();
     });
@@ -1,5 +1,7 @@
 //! Color type and default themes
 
+use crate::Vec2;
+
 /// RGBA Color as hex
 #[derive(Copy, Clone, Debug)]
 pub struct Color(pub u32);
@@ -16,16 +18,34 @@ impl Color {
     pub const fn alpha(self, alpha: u8) -> Color {
         Color((self.0 & 0xFF_FF_FF_00) | alpha as u32)
     }
+
+    /// Blend each channel from this color at `0.0` to another at `1.0`
+    ///
+    /// ```
+    /// # use immediate_mode::Color;
+    /// let gray = Color(0x00_00_00_FF).lerp(Color(0xFF_FF_FF_FF), 0.5);
+    /// let gray: [u8; 4] = gray.into();
+    /// assert_eq!(gray, [128, 128, 128, 255]);
+    /// ```
+    pub fn lerp(self, other: Color, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let (a, b): ([u8; 4], [u8; 4]) = (self.into(), other.into());
+        let channels = a.iter().zip(b.iter()).fold(0, |channels, (&a, &b)| {
+            let channel = a as f32 + (b as f32 - a as f32) * t;
+            (channels << 8) | channel.round() as u32
+        });
+        Color(channels)
+    }
 }
 
-impl Into<[u8; 4]> for Color {
-    fn into(self) -> [u8; 4] {
+impl From<Color> for [u8; 4] {
+    fn from(color: Color) -> [u8; 4] {
         // TODO: determine better method
         [
-            ((self.0 & 0xFF_00_00_00) >> 24) as u8,
-            ((self.0 & 0x00_FF_00_00) >> 16) as u8,
-            ((self.0 & 0x00_00_FF_00) >> 8) as u8,
-            (self.0 & 0x00_00_00_FF) as u8,
+            ((color.0 & 0xFF_00_00_00) >> 24) as u8,
+            ((color.0 & 0x00_FF_00_00) >> 16) as u8,
+            ((color.0 & 0x00_00_FF_00) >> 8) as u8,
+            (color.0 & 0x00_00_00_FF) as u8,
         ]
     }
 }
@@ -38,14 +58,14 @@ fn test_color_u8_conversion() {
     assert_eq!(color, [0, 255, 0, 255]);
 }
 
-impl Into<[f32; 4]> for Color {
-    fn into(self) -> [f32; 4] {
+impl From<Color> for [f32; 4] {
+    fn from(color: Color) -> [f32; 4] {
         // TODO: determine better method
         [
-            ((self.0 & 0xFF_00_00_00) >> 24) as f32 / 255.0,
-            ((self.0 & 0x00_FF_00_00) >> 16) as f32 / 255.0,
-            ((self.0 & 0x00_00_FF_00) >> 8) as f32 / 255.0,
-            (self.0 & 0x00_00_00_FF) as f32 / 255.0,
+            ((color.0 & 0xFF_00_00_00) >> 24) as f32 / 255.0,
+            ((color.0 & 0x00_FF_00_00) >> 16) as f32 / 255.0,
+            ((color.0 & 0x00_00_FF_00) >> 8) as f32 / 255.0,
+            (color.0 & 0x00_00_00_FF) as f32 / 255.0,
         ]
     }
 }
@@ -58,7 +78,8 @@ fn test_color_f32_conversion() {
     assert_eq!(color, [0.0, 1.0, 0.0, 1.0]);
 }
 
-/// Colors used in the UI
+/// Colors and shadows used in the UI
+#[derive(Debug, Clone)]
 pub struct Theme {
     /// Text color and default color of foreground elements like lines
     pub fg: Color,
@@ -85,6 +106,14 @@ pub struct Theme {
     pub selected: Color,
     /// Color of an item under the mouse or currently tabbed to
     pub hover: Color,
+    /// Color of the ring drawn around the element with keyboard focus
+    pub focus: Color,
+    /// Color of shadows cast by windows and popups
+    pub shadow: Color,
+    /// Distance in pixels over which shadows fade out
+    pub shadow_size: f32,
+    /// Distance shadows fall from the window or popup casting them
+    pub shadow_offset: Vec2,
 }
 
 impl Theme {
@@ -102,6 +131,10 @@ impl Theme {
         selected: theme::dark::BLUE,
         hover: theme::dark::AQUA,
         active: theme::dark::BRIGHT_AQUA,
+        focus: theme::dark::BRIGHT_YELLOW,
+        shadow: Color(0x00_00_00_A0),
+        shadow_size: 12.0,
+        shadow_offset: Vec2::new(0.0, 4.0),
     };
 
     /// Default light theme for UI
@@ -118,6 +151,10 @@ impl Theme {
         selected: theme::light::BLUE,
         hover: theme::light::AQUA,
         active: theme::light::BRIGHT_AQUA,
+        focus: theme::light::BRIGHT_YELLOW,
+        shadow: Color(0x00_00_00_40),
+        shadow_size: 12.0,
+        shadow_offset: Vec2::new(0.0, 4.0),
     };
 }
 
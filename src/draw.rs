@@ -1,7 +1,22 @@
 //! Low-level interface for drawing UI primitives
 
+mod gradient;
+mod path;
+mod stroke;
+mod tessellate;
+
 use crate::color::Color;
-use crate::Vec2;
+use crate::math::arc_segments;
+use crate::text::{FontAtlas, FontFamily, FontId, Fonts, GlyphId, GlyphLayout, TextBlock};
+use crate::{Bezier, Rect, Vec2};
+
+use std::f32::consts::TAU;
+
+pub use self::gradient::Gradient;
+pub use self::path::{FillRule, Path};
+use self::stroke::Rung;
+pub use self::stroke::{LineCap, LineJoin, StrokeStyle};
+use self::tessellate::{is_convex, signed_area, triangulate};
 
 /// Vertex data is always in the format (position, uv, rgba)
 ///
@@ -12,9 +27,94 @@ pub type Vert = ([f32; 2], [f32; 2], [u8; 4]);
 /// Texture coordinate for drawing fully opaque primitives
 ///
 /// For example, using this coordinate for every vertex in a triangle
-/// guarantees an opque triangle
+/// guarantees an opque triangle as long as the default texture has a white
+/// texel at this coordinate.
 pub const OPAQUE_UV: [f32; 2] = [0.0, 0.0];
 
+/// Opaque handle to a texture owned by the renderer
+///
+/// The UI never looks inside of a texture id; it only hands them back through
+/// `DrawCommand` so the renderer knows which texture to bind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextureId(u64);
+
+impl TextureId {
+    /// Texture used by primitives which do not specify one
+    ///
+    /// This is usually the font atlas, and must have a white texel at
+    /// `OPAQUE_UV`.
+    pub const DEFAULT: TextureId = TextureId(0);
+
+    /// Wrap an identifier chosen by the renderer
+    pub const fn new(id: u64) -> Self {
+        TextureId(id)
+    }
+
+    /// Identifier chosen by the renderer
+    pub const fn id(self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for TextureId {
+    fn default() -> Self {
+        TextureId::DEFAULT
+    }
+}
+
+/// A batch of indicies which are drawn with the same render state
+///
+/// Renderers should draw `index_count` indicies starting at `first_index`
+/// with `texture` bound and the scissor rectangle set to `clip_rect`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DrawCommand {
+    /// Offset of the first index drawn by this command
+    pub first_index: usize,
+    /// Number of indicies drawn by this command
+    pub index_count: usize,
+    /// Only draw inside of this rectangle; `None` draws everywhere
+    pub clip_rect: Option<Rect>,
+    /// Texture sampled by the verticies in this command
+    pub texture: TextureId,
+}
+
+impl Default for DrawCommand {
+    fn default() -> Self {
+        DrawCommand {
+            first_index: 0,
+            index_count: 0,
+            clip_rect: None,
+            texture: TextureId::DEFAULT,
+        }
+    }
+}
+
+/// Radius of each corner of a rounded rectangle
+///
+/// A single radius converts into the same radius for every corner.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CornerRadii {
+    /// Radius of the upper left corner
+    pub top_left: f32,
+    /// Radius of the upper right corner
+    pub top_right: f32,
+    /// Radius of the lower right corner
+    pub bottom_right: f32,
+    /// Radius of the lower left corner
+    pub bottom_left: f32,
+}
+
+impl From<f32> for CornerRadii {
+    fn from(radius: f32) -> Self {
+        CornerRadii {
+            top_left: radius,
+            top_right: radius,
+            bottom_right: radius,
+            bottom_left: radius,
+        }
+    }
+}
+
 /// Data needed to draw the UI
 #[derive(Debug, Clone)]
 pub struct DrawData<Vertex>
@@ -25,6 +125,13 @@ where
     pub(crate) verts: Vec<Vertex>,
     /// Index into each of the 3 vertex attribute arrays
     pub(crate) indicies: Vec<u32>,
+    /// Batches of indicies; the last command is open and covers all
+    /// indicies pushed after it started
+    pub(crate) commands: Vec<DrawCommand>,
+    /// Most distance in pixels between a curve and its segments
+    pub(crate) tolerance: f32,
+    /// Feather the edges of untextured shapes
+    pub(crate) anti_alias: bool,
 }
 
 impl<Vertex> Default for DrawData<Vertex>
@@ -35,10 +142,19 @@ where
         DrawData {
             verts: Vec::with_capacity(32),
             indicies: Vec::with_capacity(64),
+            commands: vec![DrawCommand::default()],
+            tolerance: DEFAULT_TOLERANCE,
+            anti_alias: false,
         }
     }
 }
 
+/// Default for `DrawData::set_tolerance`, a quarter of a pixel
+const DEFAULT_TOLERANCE: f32 = 0.25;
+
+/// Width in pixels of the fringe which fades anti-aliased edges
+const AA_FRINGE: f32 = 1.0;
+
 /// Very common index pattern used when pushing indicies for a quad of verts
 macro_rules! quad_indicies {
     ($first_index:expr) => {
@@ -106,8 +222,180 @@ where
         self.indicies.as_slice()
     }
 
+    /// Batches of indicies to draw, in order
+    ///
+    /// ```
+    /// use immediate_mode::{draw::DrawData, Color, Rect, Vec2};
+    ///
+    /// # type Vert = ([f32; 2], [f32; 2], [u8; 4]);
+    /// let mut draw_data = DrawData::<Vert>::default();
+    /// let clip = Rect::new(Vec2::new(0.0, 0.0), Vec2::new(5.0, 5.0));
+    ///
+    /// draw_data.rect(Color(0xFF_FF_FF_FF), (Vec2::zero(), Vec2::new(1.0, 1.0)));
+    /// draw_data.set_clip_rect(Some(clip));
+    /// draw_data.rect(Color(0xFF_FF_FF_FF), (Vec2::zero(), Vec2::new(10.0, 10.0)));
+    ///
+    /// let commands: Vec<_> = draw_data.commands().collect();
+    /// assert_eq!(commands.len(), 2);
+    /// assert_eq!(commands[1].first_index, 6);
+    /// assert_eq!(commands[1].index_count, 6);
+    /// assert_eq!(commands[1].clip_rect, Some(clip));
+    /// ```
+    pub fn commands(&self) -> impl Iterator<Item = DrawCommand> + '_ {
+        let last = self.commands.len() - 1;
+        let end = self.indicies.len();
+        self.commands
+            .iter()
+            .enumerate()
+            .map(move |(i, command)| {
+                let mut command = *command;
+                if i == last {
+                    command.index_count = end - command.first_index;
+                }
+                command
+            })
+            .filter(|command| command.index_count > 0)
+    }
+
+    /// Clip everything drawn after this call to a rectangle
+    ///
+    /// Pass `None` to stop clipping.
+    pub fn set_clip_rect(&mut self, clip_rect: Option<Rect>) {
+        let texture = self.texture();
+        self.set_state(clip_rect, texture);
+    }
+
+    /// Current clip rectangle
+    pub fn clip_rect(&self) -> Option<Rect> {
+        self.commands.last().and_then(|command| command.clip_rect)
+    }
+
+    /// Sample from a texture for everything drawn after this call
+    ///
+    /// Primitives like `rect` or `polyline` rely on `OPAQUE_UV` and should be
+    /// drawn with `TextureId::DEFAULT`.
+    pub fn set_texture(&mut self, texture: TextureId) {
+        let clip_rect = self.clip_rect();
+        self.set_state(clip_rect, texture);
+    }
+
+    /// Current texture
+    pub fn texture(&self) -> TextureId {
+        self.commands
+            .last()
+            .map_or(TextureId::DEFAULT, |command| command.texture)
+    }
+
+    /// Start a new command if the render state changed
+    fn set_state(&mut self, clip_rect: Option<Rect>, texture: TextureId) {
+        let end = self.indicies.len();
+        let current = self.commands.last_mut().expect("always one open command");
+        if current.clip_rect == clip_rect && current.texture == texture {
+            return;
+        }
+
+        if current.first_index != end {
+            current.index_count = end - current.first_index;
+            self.commands.push(DrawCommand {
+                first_index: end,
+                index_count: 0,
+                clip_rect,
+                texture,
+            });
+            return;
+        }
+
+        // nothing was drawn with the current state; continue the previous
+        // command if it matches, otherwise reuse the empty command
+        let len = self.commands.len();
+        if len > 1 {
+            let previous = self.commands[len - 2];
+            if previous.clip_rect == clip_rect && previous.texture == texture {
+                self.commands.pop();
+                return;
+            }
+        }
+        let current = self.commands.last_mut().expect("always one open command");
+        current.clip_rect = clip_rect;
+        current.texture = texture;
+    }
+
+    /// Set how closely curves are approximated by straight segments
+    ///
+    /// `tolerance` is the most distance in pixels between a curve and its
+    /// segments; smaller values use more segments.  The default is a quarter
+    /// of a pixel.
+    pub fn set_tolerance(&mut self, tolerance: f32) {
+        self.tolerance = tolerance;
+    }
+
+    /// Tolerance used to approximate curves
+    pub fn tolerance(&self) -> f32 {
+        self.tolerance
+    }
+
+    /// Feather the edges of shapes drawn after this call
+    ///
+    /// Anti-aliased shapes get a fringe about a pixel wide which fades to
+    /// transparent, which smooths their edges without multisampling at the
+    /// cost of extra verticies.  Applies to every untextured primitive, such
+    /// as `tri`, `tri_multicolor`, `rect`, the polylines, the curved shapes and
+    /// gradients; textured quads are left alone.
+    ///
+    /// ```
+    /// use immediate_mode::{draw::DrawData, Color, Vec2};
+    ///
+    /// # type Vert = ([f32; 2], [f32; 2], [u8; 4]);
+    /// let mut draw_data = DrawData::<Vert>::default();
+    /// let white = Color(0xFF_FF_FF_FF);
+    /// let (a, b, c) = (Vec2::new(0.0, 0.0), Vec2::new(10.0, 2.0), Vec2::new(3.0, 9.0));
+    ///
+    /// draw_data.set_anti_alias(true);
+    /// draw_data.tri(white, a, b, c);
+    /// // an inner and outer vertex at each corner
+    /// assert_eq!(draw_data.verts().len(), 6);
+    ///
+    /// // turn anti-aliasing off for a single call
+    /// draw_data.with_anti_alias(false, |data| data.tri(white, a, b, c));
+    /// assert_eq!(draw_data.verts().len(), 9);
+    /// assert!(draw_data.anti_alias());
+    ///
+    /// // each corner's color fades out across the fringe
+    /// draw_data.tri_multicolor((a, white), (b, white), (c, Color(0xFF_00_00_FF)));
+    /// assert_eq!(draw_data.verts().len(), 15);
+    /// ```
+    pub fn set_anti_alias(&mut self, anti_alias: bool) {
+        self.anti_alias = anti_alias;
+    }
+
+    /// Are edges anti-aliased?
+    pub fn anti_alias(&self) -> bool {
+        self.anti_alias
+    }
+
+    /// Draw with anti-aliasing turned on or off, restoring the previous
+    /// setting afterwards
+    pub fn with_anti_alias<F: FnOnce(&mut Self)>(&mut self, anti_alias: bool, draw: F) {
+        let previous = std::mem::replace(&mut self.anti_alias, anti_alias);
+        draw(self);
+        self.anti_alias = previous;
+    }
+
+    /// Remove all verticies, indicies and commands
+    pub fn clear(&mut self) {
+        self.verts.clear();
+        self.indicies.clear();
+        self.commands.clear();
+        self.commands.push(DrawCommand::default());
+    }
+
     /// Triangle with uniform color
     pub fn tri(&mut self, color: Color, a: Vec2, b: Vec2, c: Vec2) {
+        if self.anti_alias {
+            self.fill_polygon(color, &[a, b, c]);
+            return;
+        }
+
         let base_index = self.verts.len() as u32;
 
         let color: [u8; 4] = color.into();
@@ -123,6 +411,24 @@ where
     /// Triangle with vertex colors set per-vertex
     pub fn tri_multicolor(&mut self, a: (Vec2, Color), b: (Vec2, Color), c: (Vec2, Color)) {
         let base_index = self.verts.len() as u32;
+        if self.anti_alias {
+            // each corner's color fades out across the fringe
+            let vertex =
+                |point: Vec2, color: Color| V::from((point.into(), OPAQUE_UV, color.into()));
+            let corners = [a, b, c];
+            let points = [a.0, b.0, c.0];
+            for (&(point, color), offset) in corners.iter().zip(fringe_offsets(&points, false)) {
+                self.verts.extend(&[
+                    vertex(point - offset, color),
+                    vertex(point + offset, color.alpha(0)),
+                ]);
+            }
+            self.indicies
+                .extend(&[base_index, base_index + 2, base_index + 4]);
+            self.fringe(base_index, 3);
+            return;
+        }
+
         self.verts.extend(&[
             (a.0.into(), OPAQUE_UV, a.1.into()).into(),
             (b.0.into(), OPAQUE_UV, b.1.into()).into(),
@@ -134,9 +440,16 @@ where
 
     /// Add vertex data for a rectangle
     ///
-    /// Rectangle is defined by the upper left and lower right coordinates
-    /// which means it is always axis aligned to the screen coordinates.
-    pub fn rect(&mut self, color: Color, a: Vec2, b: Vec2) {
+    /// Rectangle is always axis aligned to the screen coordinates; a pair of
+    /// opposite corners can be passed in place of a `Rect`.
+    pub fn rect<R: Into<Rect>>(&mut self, color: Color, rect: R) {
+        let Rect { min: a, max: b } = rect.into();
+        if self.anti_alias {
+            let corners = [a, Vec2::new(b.x, a.y), b, Vec2::new(a.x, b.y)];
+            self.fill_polygon(color, &corners);
+            return;
+        }
+
         let base_index = self.verts.len() as u32;
 
         let color: [u8; 4] = color.into();
@@ -151,30 +464,159 @@ where
 
     /// Add vertex data for a rectangle with specified UV coords
     ///
-    /// Rectangle and its UVs defined by the upper left and lower right
-    /// coordinates which means they are always axis aligned to the
-    /// screen coordinates.
-    pub fn rect_uv(&mut self, color: Color, (a, uv_a): (Vec2, Vec2), (b, uv_b): (Vec2, Vec2)) {
+    /// Rectangle is always axis aligned to the screen coordinates; a pair of
+    /// opposite corners can be passed in place of a `Rect`.  `uv_min` is
+    /// sampled at the upper left corner and `uv_max` at the lower right.
+    /// UVs are used as given, so swapping them flips the texture.  The UVs
+    /// sample from the current texture.
+    pub fn rect_uv<R: Into<Rect>>(&mut self, color: Color, rect: R, uv_min: Vec2, uv_max: Vec2) {
+        let Rect { min: a, max: b } = rect.into();
         let base_index = self.verts.len() as u32;
 
         let color: [u8; 4] = color.into();
         self.verts.extend(&[
-            ([a.x, a.y], [uv_a.x, uv_a.y], color).into(),
-            ([a.x, b.y], [uv_a.x, uv_b.y], color).into(),
-            ([b.x, a.y], [uv_b.x, uv_a.y], color).into(),
-            ([b.x, b.y], [uv_b.x, uv_b.y], color).into(),
+            ([a.x, a.y], [uv_min.x, uv_min.y], color).into(),
+            ([a.x, b.y], [uv_min.x, uv_max.y], color).into(),
+            ([b.x, a.y], [uv_max.x, uv_min.y], color).into(),
+            ([b.x, b.y], [uv_max.x, uv_max.y], color).into(),
         ]);
         self.indicies.extend(&quad_indicies![base_index])
     }
 
-    /// Draw a line with polygons
+    /// Draw part of a texture tinted by a color
     ///
-    /// The line will have two verticies per point on the miter line, that is,
-    /// the verticies are aligned to the join between segments so it looks like
-    /// they cleanly join.
+    /// Images are batched with anything else drawn from the same texture;
+    /// drawing with a different texture starts a new `DrawCommand`.  UVs are
+    /// sampled as described by `rect_uv`, so swapping them flips the image.
     ///
-    /// This means that only 2 verts per point are generated, but the position
-    /// for each vert requires more math to compute compared to `rect_polyline`
+    /// ```
+    /// use immediate_mode::{draw::{DrawData, TextureId}, Color, Vec2};
+    ///
+    /// # type Vert = ([f32; 2], [f32; 2], [u8; 4]);
+    /// let mut draw_data = DrawData::<Vert>::default();
+    /// let icon = TextureId::new(1);
+    /// let white = Color(0xFF_FF_FF_FF);
+    /// let (uv_min, uv_max) = (Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0));
+    ///
+    /// draw_data.image(icon, (Vec2::new(0.0, 0.0), Vec2::new(16.0, 16.0)), uv_min, uv_max, white);
+    /// draw_data.image(icon, (Vec2::new(16.0, 0.0), Vec2::new(32.0, 16.0)), uv_min, uv_max, white);
+    /// draw_data.rect(white, (Vec2::new(0.0, 16.0), Vec2::new(32.0, 32.0)));
+    ///
+    /// let textures: Vec<_> = draw_data.commands().map(|c| c.texture).collect();
+    /// assert_eq!(textures, vec![icon, TextureId::DEFAULT]);
+    ///
+    /// // a render target with y pointing up is drawn upside down
+    /// let target = TextureId::new(2);
+    /// let rect = (Vec2::new(0.0, 32.0), Vec2::new(32.0, 64.0));
+    /// draw_data.image(target, rect, Vec2::new(0.0, 1.0), Vec2::new(1.0, 0.0), white);
+    /// let (pos, uv, _) = draw_data.verts()[draw_data.verts().len() - 4];
+    /// assert_eq!((pos, uv), ([0.0, 32.0], [0.0, 1.0]));
+    /// ```
+    pub fn image<R: Into<Rect>>(
+        &mut self,
+        texture: TextureId,
+        rect: R,
+        uv_min: Vec2,
+        uv_max: Vec2,
+        tint: Color,
+    ) {
+        let previous = self.texture();
+        self.set_texture(texture);
+        self.rect_uv(tint, rect, uv_min, uv_max);
+        self.set_texture(previous);
+    }
+
+    /// Draw a string of text with its first line's upper left corner at `pos`
+    ///
+    /// Glyphs of the family's fonts are rasterized into the atlas as needed
+    /// and drawn as quads sampling `TextureId::DEFAULT`, which must be the
+    /// atlas texture.  Explicit newlines start a new line.
+    ///
+    /// ```
+    /// use immediate_mode::text::{Font, FontFamily, Fonts, GlyphMode};
+    /// use immediate_mode::{draw::DrawData, Color, Vec2};
+    ///
+    /// # type Vert = ([f32; 2], [f32; 2], [u8; 4]);
+    /// let data = include_bytes!("../dev/fonts/Source/SourceSansPro-Regular.ttf");
+    /// let mut fonts = Fonts::new(256, 256);
+    /// let sans = fonts.add_font(Font::from_bytes(&data[..]).unwrap(), GlyphMode::Coverage);
+    /// fonts.push_font(FontFamily::PROPORTIONAL, sans);
+    /// let mut draw_data = DrawData::<Vert>::default();
+    ///
+    /// let white = Color(0xFF_FF_FF_FF);
+    /// draw_data.text(&mut fonts, FontFamily::PROPORTIONAL, 16.0, Vec2::zero(), white, "Hi there");
+    ///
+    /// // one quad for each glyph, spaces have nothing to draw
+    /// assert_eq!(draw_data.verts().len(), 7 * 4);
+    /// ```
+    pub fn text(
+        &mut self,
+        fonts: &mut Fonts,
+        family: FontFamily,
+        size: f32,
+        pos: Vec2,
+        color: Color,
+        text: &str,
+    ) {
+        let previous = self.texture();
+        self.set_texture(TextureId::DEFAULT);
+
+        // the atlas owns the fonts, so glyphs are laid out before they are
+        // added to it
+        let glyphs: Vec<_> = {
+            let (ids, faces) = fonts.faces(family);
+            GlyphLayout::new(&faces, size, pos, text)
+                .map(|laid| (ids[laid.font], laid))
+                .collect()
+        };
+        let atlas = fonts.atlas_mut();
+        for (font, laid) in glyphs {
+            self.glyph(atlas, font, size, laid.id, laid.origin, color);
+        }
+
+        self.set_texture(previous);
+    }
+
+    /// Draw text laid out by `TextBlock`
+    ///
+    /// The block should be laid out with the same fonts.
+    pub fn text_block(&mut self, fonts: &mut Fonts, color: Color, block: &TextBlock) {
+        let previous = self.texture();
+        self.set_texture(TextureId::DEFAULT);
+
+        let atlas = fonts.atlas_mut();
+        for glyph in &block.glyphs {
+            self.glyph(atlas, glyph.font, block.size, glyph.id, glyph.origin, color);
+        }
+
+        self.set_texture(previous);
+    }
+
+    /// Draw a single glyph with its origin on the baseline at `origin`
+    fn glyph(
+        &mut self,
+        atlas: &mut FontAtlas,
+        font: FontId,
+        size: f32,
+        id: GlyphId,
+        origin: Vec2,
+        color: Color,
+    ) {
+        if let Some(glyph) = atlas.glyph(font, id, size) {
+            // snap glyphs to pixels since they were rasterized at a pixel
+            // offset of zero
+            let quad = glyph.bounds.translate(origin.map(f32::round));
+            let uv = atlas.uv(&glyph);
+            self.rect_uv(color, quad, uv.min, uv.max);
+        }
+    }
+
+    /// Draw a line with polygons
+    ///
+    /// Segments meet at joins and the ends are capped as described by the
+    /// stroke style; a thickness alone draws mitered joins and butt caps.
+    /// Straight and gently curving lines have only two verticies per point,
+    /// while sharp corners are beveled or rounded instead of spiking.
     /// ```
     /// use immediate_mode::{ draw::DrawData, Color, Vec2 };
     ///
@@ -192,70 +634,239 @@ where
     /// assert_eq!(points.len() * 2, draw_data.verts().len());
     /// assert_eq!((points.len()-1) * 6, draw_data.indicies().len());
     /// ```
-    pub fn polyline(&mut self, color: Color, thickness: f32, points: &[Vec2]) {
-        // line must connect two points
-        if points.len() < 2 {
-            return;
-        }
-
-        let color: [u8; 4] = color.into();
-        let thickness = thickness * 0.5;
+    ///
+    /// Plots of noisy data double back sharply, so bevel or round them:
+    ///
+    /// ```
+    /// use immediate_mode::{draw::{DrawData, LineJoin, StrokeStyle}, Color, Vec2};
+    ///
+    /// # type Vert = ([f32; 2], [f32; 2], [u8; 4]);
+    /// let mut draw_data = DrawData::<Vert>::default();
+    /// let points = &[
+    ///     Vec2::new(0.0, 0.0),
+    ///     Vec2::new(100.0, 0.0),
+    ///     Vec2::new(0.0, 1.0),
+    /// ];
+    /// let style = StrokeStyle {
+    ///     join: LineJoin::Bevel,
+    ///     ..StrokeStyle::from(4.0)
+    /// };
+    /// draw_data.polyline(Color(0xFF_FF_FF_FF), style, points);
+    ///
+    /// // nothing reaches far past the sharp corner
+    /// for &([x, _], _, _) in draw_data.verts() {
+    ///     assert!(x < 104.0);
+    /// }
+    /// ```
+    pub fn polyline<'a, S>(&mut self, color: Color, stroke: S, points: &[Vec2])
+    where
+        S: Into<StrokeStyle<'a>>,
+    {
+        self.stroke(color, &stroke.into(), points, false);
+    }
 
-        // Draw the line with two vertices per point.  The verts are placed
-        // on the miter line.  This line is essentially the intersection of
-        // the rectangles which form the segments on the line, forming a corner
+    /// Draw the outline of a closed shape
+    ///
+    /// Like `polyline`, except the last point joins back to the first so
+    /// every corner is joined and there are no caps.  Repeating the first
+    /// point at the end is allowed.
+    ///
+    /// ```
+    /// use immediate_mode::{draw::DrawData, Color, Vec2};
+    ///
+    /// # type Vert = ([f32; 2], [f32; 2], [u8; 4]);
+    /// let mut draw_data = DrawData::<Vert>::default();
+    ///
+    /// // a square border with a mitered join at each corner
+    /// let points = &[
+    ///     Vec2::new(0.0, 0.0),
+    ///     Vec2::new(10.0, 0.0),
+    ///     Vec2::new(10.0, 10.0),
+    ///     Vec2::new(0.0, 10.0),
+    /// ];
+    /// draw_data.polygon_outline(Color(0xFF_FF_FF_FF), 2.0, points);
+    ///
+    /// assert_eq!(points.len() * 2, draw_data.verts().len());
+    /// assert_eq!(points.len() * 6, draw_data.indicies().len());
+    /// ```
+    pub fn polygon_outline<'a, S>(&mut self, color: Color, stroke: S, points: &[Vec2])
+    where
+        S: Into<StrokeStyle<'a>>,
+    {
+        self.stroke(color, &stroke.into(), points, true);
+    }
 
-        self.verts.reserve(2 * points.len()); // 2 verts per point
-        self.indicies.reserve((points.len() - 1) * 6); // 2 tris per segment
+    /// Fill a polygon which does not cross itself
+    ///
+    /// Convex polygons are filled with a fan of triangles, while concave
+    /// polygons are split into triangles by clipping off one corner at a
+    /// time.  Points may wind either way.
+    ///
+    /// ```
+    /// use immediate_mode::{draw::DrawData, Color, Vec2};
+    ///
+    /// # type Vert = ([f32; 2], [f32; 2], [u8; 4]);
+    /// let mut draw_data = DrawData::<Vert>::default();
+    ///
+    /// // an arrow pointing right, concave where the head meets the shaft
+    /// let arrow = &[
+    ///     Vec2::new(0.0, 4.0),
+    ///     Vec2::new(10.0, 4.0),
+    ///     Vec2::new(10.0, 0.0),
+    ///     Vec2::new(20.0, 8.0),
+    ///     Vec2::new(10.0, 16.0),
+    ///     Vec2::new(10.0, 12.0),
+    ///     Vec2::new(0.0, 12.0),
+    /// ];
+    /// draw_data.polygon_fill(Color(0xFF_FF_FF_FF), arrow);
+    ///
+    /// assert_eq!(draw_data.verts().len(), arrow.len());
+    /// assert_eq!(draw_data.indicies().len(), (arrow.len() - 2) * 3);
+    /// ```
+    pub fn polygon_fill(&mut self, color: Color, points: &[Vec2]) {
+        if is_convex(points) {
+            self.fill_polygon(color, points);
+        } else {
+            self.polygon_fill_with_holes(color, points, &[]);
+        }
+    }
 
-        // Place the first points perpendicular to the line segment from
-        // the first to second point
-        let df = points[0] - points[1];
-        let nf = df.normal().unit() * thickness;
-        let first_index = self.verts.len() as u32;
-        self.verts.extend(&[
-            ((points[0] + nf).into(), OPAQUE_UV, color).into(),
-            ((points[0] - nf).into(), OPAQUE_UV, color).into(),
-        ]);
-        // push indicies joining this point to the next point's verts
-        self.indicies.extend(&quad_indicies![first_index]);
+    /// Fill a polygon leaving holes inside of it empty
+    ///
+    /// Neither the outline nor the holes may cross themselves or each other.
+    ///
+    /// ```
+    /// use immediate_mode::{draw::DrawData, Color, Vec2};
+    ///
+    /// # type Vert = ([f32; 2], [f32; 2], [u8; 4]);
+    /// let mut draw_data = DrawData::<Vert>::default();
+    ///
+    /// // a square frame
+    /// let outline = &[
+    ///     Vec2::new(0.0, 0.0),
+    ///     Vec2::new(10.0, 0.0),
+    ///     Vec2::new(10.0, 10.0),
+    ///     Vec2::new(0.0, 10.0),
+    /// ];
+    /// let hole: &[Vec2] = &[
+    ///     Vec2::new(3.0, 3.0),
+    ///     Vec2::new(7.0, 3.0),
+    ///     Vec2::new(7.0, 7.0),
+    ///     Vec2::new(3.0, 7.0),
+    /// ];
+    /// draw_data.polygon_fill_with_holes(Color(0xFF_FF_FF_FF), outline, &[hole]);
+    ///
+    /// assert_eq!(draw_data.verts().len(), 8);
+    /// ```
+    pub fn polygon_fill_with_holes(&mut self, color: Color, outline: &[Vec2], holes: &[&[Vec2]]) {
+        if outline.len() < 3 {
+            return;
+        }
 
-        // iterate over pairs of indicies
-        for i1 in 1..(points.len() - 1) {
-            let p0 = points[i1 - 1];
-            let p1 = points[i1];
-            let p2 = points[i1 + 1];
+        let holes: Vec<&[Vec2]> = holes.iter().copied().filter(|h| h.len() >= 3).collect();
+        let triangles = triangulate(outline, &holes);
+        let rings: Vec<&[Vec2]> = Some(outline).into_iter().chain(holes).collect();
+        self.fill_mesh(color, &rings, &triangles);
+    }
 
-            // calculate the direction of the line going into the point and its normal
-            let d_in = p1 - p0;
-            let n01 = d_in.normal().unit();
+    /// Draw a quadratic Bézier curve
+    ///
+    /// The curve is flattened into segments which stray at most the
+    /// tolerance from it, then stroked like `polyline`.
+    pub fn bezier_quadratic<'a, S>(
+        &mut self,
+        color: Color,
+        stroke: S,
+        from: Vec2,
+        ctrl: Vec2,
+        to: Vec2,
+    ) where
+        S: Into<StrokeStyle<'a>>,
+    {
+        let curve = Bezier::Quadratic { from, ctrl, to };
+        let points: Vec<_> = curve.flatten(self.tolerance).collect();
+        self.stroke(color, &stroke.into(), &points, false);
+    }
 
-            // calculate the tangent of join between lines and get its normal
-            let miter = ((p2 - p1).unit() + (p1 - p0).unit()).unit().normal();
+    /// Draw a cubic Bézier curve
+    ///
+    /// Flattened and stroked like `bezier_quadratic`.
+    ///
+    /// ```
+    /// use immediate_mode::{draw::DrawData, Color, Vec2};
+    ///
+    /// # type Vert = ([f32; 2], [f32; 2], [u8; 4]);
+    /// let mut draw_data = DrawData::<Vert>::default();
+    /// let white = Color(0xFF_FF_FF_FF);
+    ///
+    /// // a wire between two nodes of a graph
+    /// let (from, to) = (Vec2::new(0.0, 0.0), Vec2::new(200.0, 80.0));
+    /// let (ctrl1, ctrl2) = (Vec2::new(100.0, 0.0), Vec2::new(100.0, 80.0));
+    /// draw_data.bezier_cubic(white, 2.0, from, ctrl1, ctrl2, to);
+    /// let smooth = draw_data.verts().len();
+    ///
+    /// // a looser tolerance needs fewer segments
+    /// draw_data.clear();
+    /// draw_data.set_tolerance(4.0);
+    /// draw_data.bezier_cubic(white, 2.0, from, ctrl1, ctrl2, to);
+    /// assert!(draw_data.verts().len() < smooth);
+    /// ```
+    pub fn bezier_cubic<'a, S>(
+        &mut self,
+        color: Color,
+        stroke: S,
+        from: Vec2,
+        ctrl1: Vec2,
+        ctrl2: Vec2,
+        to: Vec2,
+    ) where
+        S: Into<StrokeStyle<'a>>,
+    {
+        let curve = Bezier::Cubic {
+            from,
+            ctrl1,
+            ctrl2,
+            to,
+        };
+        let points: Vec<_> = curve.flatten(self.tolerance).collect();
+        self.stroke(color, &stroke.into(), &points, false);
+    }
 
-            // project the miter line onto the normal and use it to calculate the
-            // length of the miter line needed to join the line segments
-            let length = thickness / miter.dot(n01);
+    /// Fill the inside of a path
+    ///
+    /// Every piece of the path is closed, and the fill rule decides whether
+    /// pieces inside of others are filled or cut holes.  Pieces may not cross
+    /// themselves or each other.
+    pub fn path_fill(&mut self, color: Color, path: &Path, rule: FillRule) {
+        let rings: Vec<Vec<Vec2>> = path
+            .flatten(self.tolerance)
+            .into_iter()
+            .map(|(points, _)| points)
+            .filter(|points| points.len() >= 3)
+            .collect();
 
-            // push indicies joining this point to the _next_ point
-            // but only push the verticies for this point along the miter line
-            let first_index = self.verts.len() as u32;
-            self.verts.extend(&[
-                ((p1 - miter * length).into(), OPAQUE_UV, color).into(),
-                ((p1 + miter * length).into(), OPAQUE_UV, color).into(),
-            ]);
-            self.indicies.extend(&quad_indicies![first_index]);
+        for (outline, holes) in path::fill_regions(&rings, rule) {
+            if holes.is_empty() {
+                self.polygon_fill(color, &rings[outline]);
+            } else {
+                let holes: Vec<&[Vec2]> = holes.iter().map(|&hole| &rings[hole][..]).collect();
+                self.polygon_fill_with_holes(color, &rings[outline], &holes);
+            }
         }
+    }
 
-        // Place the last points perpendicular to the line segment as with the
-        // first points, indicies have already been pushed on
-        let last = points.len() - 1;
-        let dl = points[last] - points[last - 1];
-        let nl = dl.normal().unit() * thickness;
-        self.verts.extend(&[
-            ((points[last] - nl).into(), OPAQUE_UV, color).into(),
-            ((points[last] + nl).into(), OPAQUE_UV, color).into(),
-        ]);
+    /// Draw the lines and curves of a path
+    ///
+    /// Closed pieces are joined all the way around like `polygon_outline`
+    /// while open pieces are capped like `polyline`.
+    pub fn path_stroke<'a, S>(&mut self, color: Color, stroke: S, path: &Path)
+    where
+        S: Into<StrokeStyle<'a>>,
+    {
+        let style = stroke.into();
+        for (points, closed) in path.flatten(self.tolerance) {
+            self.stroke(color, &style, &points, closed);
+        }
     }
 
     /// Generates a line from rectangles
@@ -289,6 +900,27 @@ where
             return;
         }
 
+        if self.anti_alias {
+            // a separate band for each segment
+            for segment in points.windows(2) {
+                let n = (segment[1] - segment[0]).normal().unit();
+                let rungs = [
+                    Rung {
+                        point: segment[0],
+                        left: n,
+                        right: n,
+                    },
+                    Rung {
+                        point: segment[1],
+                        left: n,
+                        right: n,
+                    },
+                ];
+                self.stroke_band(color, &rungs, thickness, false);
+            }
+            return;
+        }
+
         let color: [u8; 4] = color.into();
         let thickness = thickness * 0.5;
 
@@ -329,4 +961,596 @@ where
             self.indicies.extend(&quad_indicies![first_index]);
         }
     }
+
+    /// Filled circle
+    ///
+    /// The number of segments depends on the radius and the tolerance, so
+    /// small circles stay cheap.
+    ///
+    /// ```
+    /// use immediate_mode::{draw::DrawData, Color, Vec2};
+    ///
+    /// # type Vert = ([f32; 2], [f32; 2], [u8; 4]);
+    /// let mut draw_data = DrawData::<Vert>::default();
+    /// let white = Color(0xFF_FF_FF_FF);
+    ///
+    /// draw_data.circle(white, Vec2::new(10.0, 10.0), 2.0);
+    /// let small = draw_data.verts().len();
+    ///
+    /// draw_data.clear();
+    /// draw_data.circle(white, Vec2::new(100.0, 100.0), 80.0);
+    /// assert!(draw_data.verts().len() > small);
+    /// ```
+    pub fn circle(&mut self, color: Color, center: Vec2, radius: f32) {
+        self.ellipse(color, center, Vec2::new(radius, radius));
+    }
+
+    /// Outline of a circle centered on its radius
+    pub fn circle_outline<'a, S>(&mut self, color: Color, center: Vec2, radius: f32, stroke: S)
+    where
+        S: Into<StrokeStyle<'a>>,
+    {
+        self.ellipse_outline(color, center, Vec2::new(radius, radius), stroke);
+    }
+
+    /// Filled ellipse with horizontal and vertical radii
+    pub fn ellipse(&mut self, color: Color, center: Vec2, radii: Vec2) {
+        let points = self.arc_points(center, radii, 0.0, TAU, true);
+        self.fill_polygon(color, &points);
+    }
+
+    /// Outline of an ellipse centered on its edge
+    pub fn ellipse_outline<'a, S>(&mut self, color: Color, center: Vec2, radii: Vec2, stroke: S)
+    where
+        S: Into<StrokeStyle<'a>>,
+    {
+        let points = self.arc_points(center, radii, 0.0, TAU, true);
+        self.stroke(color, &stroke.into(), &points, true);
+    }
+
+    /// Filled slice of a circle between two angles
+    ///
+    /// Angles are in radians, starting from the positive x axis and turning
+    /// clockwise on screen since y points down.
+    pub fn arc(&mut self, color: Color, center: Vec2, radius: f32, start: f32, end: f32) {
+        let radii = Vec2::new(radius, radius);
+        let arc = self.arc_points(center, radii, start, end, false);
+        let points: Vec<_> = Some(center).into_iter().chain(arc).collect();
+        self.fill_polygon(color, &points);
+    }
+
+    /// Curved edge of a circle between two angles
+    ///
+    /// Angles are measured as in `arc`, and the ends are capped as described
+    /// by the stroke style.
+    ///
+    /// ```
+    /// use immediate_mode::{draw::DrawData, Color, Vec2};
+    /// use std::f32::consts::PI;
+    ///
+    /// # type Vert = ([f32; 2], [f32; 2], [u8; 4]);
+    /// let mut draw_data = DrawData::<Vert>::default();
+    /// let white = Color(0xFF_FF_FF_FF);
+    ///
+    /// // a spinner a quarter of the way around
+    /// draw_data.arc_outline(white, Vec2::new(20.0, 20.0), 10.0, 0.0, PI / 2.0, 2.0);
+    ///
+    /// // two verticies for each point along the arc
+    /// let points = draw_data.verts().len() / 2;
+    /// assert_eq!(draw_data.indicies().len(), (points - 1) * 6);
+    /// ```
+    pub fn arc_outline<'a, S>(
+        &mut self,
+        color: Color,
+        center: Vec2,
+        radius: f32,
+        start: f32,
+        end: f32,
+        stroke: S,
+    ) where
+        S: Into<StrokeStyle<'a>>,
+    {
+        let radii = Vec2::new(radius, radius);
+        let points = self.arc_points(center, radii, start, end, false);
+        self.stroke(color, &stroke.into(), &points, false);
+    }
+
+    /// Filled rectangle with rounded corners
+    ///
+    /// Radii larger than half of the rectangle's width or height are reduced
+    /// to fit.  Corners with no radius stay sharp and need no extra
+    /// verticies.
+    ///
+    /// ```
+    /// use immediate_mode::{draw::{CornerRadii, DrawData}, Color, Rect, Vec2};
+    ///
+    /// # type Vert = ([f32; 2], [f32; 2], [u8; 4]);
+    /// let mut draw_data = DrawData::<Vert>::default();
+    /// let white = Color(0xFF_FF_FF_FF);
+    /// let rect = Rect::new(Vec2::zero(), Vec2::new(100.0, 40.0));
+    ///
+    /// // a tab with only its top corners rounded
+    /// let radii = CornerRadii {
+    ///     top_left: 6.0,
+    ///     top_right: 6.0,
+    ///     ..CornerRadii::default()
+    /// };
+    /// draw_data.rect_rounded(white, rect, radii);
+    ///
+    /// // without radii it is an ordinary quad
+    /// draw_data.clear();
+    /// draw_data.rect_rounded(white, rect, 0.0);
+    /// assert_eq!(draw_data.verts().len(), 4);
+    /// assert_eq!(draw_data.indicies().len(), 6);
+    /// ```
+    pub fn rect_rounded<R, C>(&mut self, color: Color, rect: R, radii: C)
+    where
+        R: Into<Rect>,
+        C: Into<CornerRadii>,
+    {
+        let points = self.rounded_rect_points(rect.into(), radii.into());
+        self.fill_polygon(color, &points);
+    }
+
+    /// Border of a rectangle with rounded corners, centered on its edges
+    pub fn rect_rounded_outline<'a, R, C, S>(&mut self, color: Color, rect: R, radii: C, stroke: S)
+    where
+        R: Into<Rect>,
+        C: Into<CornerRadii>,
+        S: Into<StrokeStyle<'a>>,
+    {
+        let points = self.rounded_rect_points(rect.into(), radii.into());
+        self.stroke(color, &stroke.into(), &points, true);
+    }
+
+    /// Rectangle filled with a gradient
+    pub fn rect_gradient<R: Into<Rect>>(&mut self, rect: R, gradient: &Gradient) {
+        let rect = rect.into();
+        let corners = [
+            rect.min,
+            Vec2::new(rect.max.x, rect.min.y),
+            rect.max,
+            Vec2::new(rect.min.x, rect.max.y),
+        ];
+        self.fill_gradient(gradient, &corners);
+    }
+
+    /// Rectangle with rounded corners filled with a gradient
+    pub fn rect_rounded_gradient<R, C>(&mut self, rect: R, radii: C, gradient: &Gradient)
+    where
+        R: Into<Rect>,
+        C: Into<CornerRadii>,
+    {
+        let points = self.rounded_rect_points(rect.into(), radii.into());
+        self.fill_gradient(gradient, &points);
+    }
+
+    /// Circle filled with a gradient
+    ///
+    /// ```
+    /// use immediate_mode::{draw::{DrawData, Gradient}, Color, Vec2};
+    ///
+    /// # type Vert = ([f32; 2], [f32; 2], [u8; 4]);
+    /// let mut draw_data = DrawData::<Vert>::default();
+    ///
+    /// // a highlight toward the upper left of a ball
+    /// let center = Vec2::new(50.0, 50.0);
+    /// let light = Vec2::new(40.0, 40.0);
+    /// let shading = Gradient::radial(light, 50.0, Color(0xFF_FF_FF_FF), Color(0x20_20_60_FF));
+    /// draw_data.circle_gradient(center, 40.0, &shading);
+    /// assert!(draw_data.verts().len() > 0);
+    /// ```
+    pub fn circle_gradient(&mut self, center: Vec2, radius: f32, gradient: &Gradient) {
+        let radii = Vec2::new(radius, radius);
+        let points = self.arc_points(center, radii, 0.0, TAU, true);
+        self.fill_gradient(gradient, &points);
+    }
+
+    /// Soft shadow of a rectangle with rounded corners
+    ///
+    /// The shadow is the rectangle moved by `offset`, fading out over `blur`
+    /// pixels centered on its edges; draw it before the shape casting it.
+    /// Without an offset and with a bright color it is a glow instead.
+    ///
+    /// ```
+    /// use immediate_mode::{draw::DrawData, Color, Rect, Vec2};
+    ///
+    /// # type Vert = ([f32; 2], [f32; 2], [u8; 4]);
+    /// let mut draw_data = DrawData::<Vert>::default();
+    /// let window = Rect::new(Vec2::new(10.0, 10.0), Vec2::new(110.0, 80.0));
+    ///
+    /// let shadow = Color(0x00_00_00_A0);
+    /// draw_data.shadow(window, 4.0, 12.0, Vec2::new(0.0, 4.0), shadow);
+    /// draw_data.rect_rounded(Color(0x32_30_2F_FF), window, 4.0);
+    ///
+    /// // the shadow fades out completely at its edges
+    /// assert!(draw_data.verts().iter().any(|&(_, _, [_, _, _, a])| a == 0));
+    /// ```
+    pub fn shadow<R: Into<Rect>>(
+        &mut self,
+        rect: R,
+        radius: f32,
+        blur: f32,
+        offset: Vec2,
+        color: Color,
+    ) {
+        use std::f32::consts::{FRAC_PI_2, PI};
+
+        let rect = rect.into().translate(offset);
+        if blur <= 0.0 {
+            self.rect_rounded(color, rect, radius);
+            return;
+        }
+
+        // rings of points around the solid core at increasing distances
+        // from the centers of its corners
+        let core = rect.shrink(blur * 0.5);
+        let radius = (radius - blur * 0.5)
+            .max(0.0)
+            .min(core.width().min(core.height()) * 0.5);
+        let corners = [
+            (Vec2::new(core.min.x + radius, core.min.y + radius), PI),
+            (
+                Vec2::new(core.max.x - radius, core.min.y + radius),
+                1.5 * PI,
+            ),
+            (Vec2::new(core.max.x - radius, core.max.y - radius), 0.0),
+            (
+                Vec2::new(core.min.x + radius, core.max.y - radius),
+                FRAC_PI_2,
+            ),
+        ];
+        let segments = arc_segments(radius + blur, FRAC_PI_2, self.tolerance);
+        let directions: Vec<(Vec2, Vec2)> = corners
+            .iter()
+            .flat_map(|&(center, start)| {
+                (0..=segments).map(move |i| {
+                    let angle = start + FRAC_PI_2 * i as f32 / segments as f32;
+                    let (sin, cos) = angle.sin_cos();
+                    (center, Vec2::new(cos, sin))
+                })
+            })
+            .collect();
+
+        let bands = ((blur / 4.0).ceil() as usize).clamp(2, 8);
+        let count = directions.len() as u32;
+        let base_index = self.verts.len() as u32;
+        let [_, _, _, alpha]: [u8; 4] = color.into();
+        self.verts.reserve(directions.len() * (bands + 1));
+        for band in 0..=bands {
+            // fade smoothly, reaching half of the color at the edge
+            let t = band as f32 / bands as f32;
+            let fade = 1.0 - t * t * (3.0 - 2.0 * t);
+            let color: [u8; 4] = color.alpha((alpha as f32 * fade).round() as u8).into();
+            let distance = radius + blur * t;
+            self.verts
+                .extend(directions.iter().map(|&(center, direction)| {
+                    V::from(((center + direction * distance).into(), OPAQUE_UV, color))
+                }));
+        }
+
+        // fan across the core, then a band of quads between each ring
+        self.indicies
+            .reserve((count as usize - 2) * 3 + bands * count as usize * 6);
+        for i in 1..count - 1 {
+            self.indicies
+                .extend(&[base_index, base_index + i, base_index + i + 1]);
+        }
+        for band in 0..bands as u32 {
+            let inner = base_index + band * count;
+            let outer = inner + count;
+            for i in 0..count {
+                let j = (i + 1) % count;
+                self.indicies.extend(&[
+                    inner + i,
+                    outer + i,
+                    inner + j,
+                    outer + i,
+                    inner + j,
+                    outer + j,
+                ]);
+            }
+        }
+    }
+
+    /// Points around a rounded rectangle, clockwise from its upper left
+    /// corner
+    fn rounded_rect_points(&self, rect: Rect, radii: CornerRadii) -> Vec<Vec2> {
+        use std::f32::consts::{FRAC_PI_2, PI};
+
+        let fit = |radius: f32| radius.max(0.0).min(rect.width().min(rect.height()) * 0.5);
+        let corners = [
+            (
+                fit(radii.top_left),
+                Vec2::new(rect.min.x, rect.min.y),
+                Vec2::new(1.0, 1.0),
+                PI,
+            ),
+            (
+                fit(radii.top_right),
+                Vec2::new(rect.max.x, rect.min.y),
+                Vec2::new(-1.0, 1.0),
+                1.5 * PI,
+            ),
+            (
+                fit(radii.bottom_right),
+                Vec2::new(rect.max.x, rect.max.y),
+                Vec2::new(-1.0, -1.0),
+                0.0,
+            ),
+            (
+                fit(radii.bottom_left),
+                Vec2::new(rect.min.x, rect.max.y),
+                Vec2::new(1.0, -1.0),
+                FRAC_PI_2,
+            ),
+        ];
+
+        let mut points = Vec::new();
+        for &(radius, corner, inward, start) in &corners {
+            if radius > 0.0 {
+                let center = corner + inward * radius;
+                let radii = Vec2::new(radius, radius);
+                points.extend(self.arc_points(center, radii, start, start + FRAC_PI_2, false));
+            } else {
+                points.push(corner);
+            }
+        }
+        points
+    }
+
+    /// Fill a polygon with a fan from its first point
+    ///
+    /// Every point must be visible from the first point, as in convex
+    /// polygons or slices of a circle.
+    fn fill_polygon(&mut self, color: Color, points: &[Vec2]) {
+        if points.len() < 3 {
+            return;
+        }
+
+        let mut triangles = Vec::with_capacity((points.len() - 2) * 3);
+        for i in 1..points.len() as u32 - 1 {
+            triangles.extend(&[0, i, i + 1]);
+        }
+        self.fill_mesh(color, &[points], &triangles);
+    }
+
+    /// Fill triangles between the points of rings
+    ///
+    /// Indicies refer to the points of every ring in order.  Anti-aliased
+    /// rings are inset by half of the fringe and surrounded by the fringe;
+    /// the first ring is the outline and the rest are holes.
+    fn fill_mesh(&mut self, color: Color, rings: &[&[Vec2]], triangles: &[u32]) {
+        let base_index = self.verts.len() as u32;
+        let solid: [u8; 4] = color.into();
+
+        if !self.anti_alias {
+            let points = rings.iter().flat_map(|ring| ring.iter());
+            self.verts
+                .extend(points.map(|&point| V::from((point.into(), OPAQUE_UV, solid))));
+            self.indicies
+                .extend(triangles.iter().map(|&i| base_index + i));
+            return;
+        }
+
+        let clear: [u8; 4] = color.alpha(0).into();
+
+        // an inner solid and outer clear vertex at each point
+        let count: usize = rings.iter().map(|ring| ring.len()).sum();
+        self.verts.reserve(count * 2);
+        self.indicies.reserve(triangles.len() + count * 6);
+        for (r, ring) in rings.iter().enumerate() {
+            let first = self.verts.len() as u32;
+            for (&point, offset) in ring.iter().zip(fringe_offsets(ring, r > 0)) {
+                self.verts.extend(&[
+                    ((point - offset).into(), OPAQUE_UV, solid).into(),
+                    ((point + offset).into(), OPAQUE_UV, clear).into(),
+                ]);
+            }
+            self.fringe(first, ring.len() as u32);
+        }
+
+        // triangles across the inner verticies
+        self.indicies
+            .extend(triangles.iter().map(|&i| base_index + 2 * i));
+    }
+
+    /// Fill a convex polygon with a gradient
+    ///
+    /// The polygon is cut into pieces at the gradient's stops so that
+    /// blending between the colors of their verticies follows the gradient.
+    fn fill_gradient(&mut self, gradient: &Gradient, points: &[Vec2]) {
+        if points.len() < 3 {
+            return;
+        }
+
+        let offsets = if self.anti_alias {
+            fringe_offsets(points, false)
+        } else {
+            vec![Vec2::zero(); points.len()]
+        };
+        let inner: Vec<Vec2> = points
+            .iter()
+            .zip(&offsets)
+            .map(|(&point, &offset)| point - offset)
+            .collect();
+        let vertex = |point: Vec2, color: Color| V::from((point.into(), OPAQUE_UV, color.into()));
+
+        for cell in gradient::cells(gradient, &inner, self.tolerance) {
+            let base_index = self.verts.len() as u32;
+            self.verts.extend(
+                cell.iter()
+                    .map(|&point| vertex(point, gradient.color_at(point))),
+            );
+            for i in 1..cell.len() as u32 - 1 {
+                self.indicies
+                    .extend(&[base_index, base_index + i, base_index + i + 1]);
+            }
+        }
+
+        if self.anti_alias {
+            let first = self.verts.len() as u32;
+            for (&point, &offset) in inner.iter().zip(&offsets) {
+                let color = gradient.color_at(point);
+                self.verts.extend(&[
+                    vertex(point, color),
+                    vertex(point + offset * 2.0, color.alpha(0)),
+                ]);
+            }
+            self.fringe(first, points.len() as u32);
+        }
+    }
+
+    /// Join pairs of inner and outer verticies around a ring into a fringe
+    fn fringe(&mut self, first: u32, count: u32) {
+        for i in 0..count {
+            let a = first + 2 * i;
+            let b = first + 2 * ((i + 1) % count);
+            self.indicies.extend(&[a, a + 1, b, a + 1, b, b + 1]);
+        }
+    }
+
+    /// Points along an elliptical arc
+    ///
+    /// Closed arcs leave out the last point since it repeats the first.
+    fn arc_points(
+        &self,
+        center: Vec2,
+        radii: Vec2,
+        start: f32,
+        end: f32,
+        closed: bool,
+    ) -> Vec<Vec2> {
+        let angle = end - start;
+        let mut segments = arc_segments(radii.x.max(radii.y), angle, self.tolerance);
+        if closed {
+            segments = segments.max(3);
+        }
+
+        let count = if closed { segments } else { segments + 1 };
+        (0..count)
+            .map(|i| {
+                let theta = start + angle * i as f32 / segments as f32;
+                let (sin, cos) = theta.sin_cos();
+                center + Vec2::new(radii.x * cos, radii.y * sin)
+            })
+            .collect()
+    }
+
+    /// Stroke a line through points, joining its ends when it is closed
+    fn stroke(&mut self, color: Color, style: &StrokeStyle<'_>, points: &[Vec2], closed: bool) {
+        // patterns without any length are solid
+        if !style.dash.iter().any(|&length| length > 0.0) {
+            let rungs = stroke::rungs(points, style, closed, self.tolerance);
+            self.stroke_band(color, &rungs, style.thickness, closed);
+            return;
+        }
+
+        for (dash, closed) in
+            stroke::dashes(points, closed, style.dash, style.dash_phase, self.tolerance)
+        {
+            let rungs = stroke::rungs(&dash, style, closed, self.tolerance);
+            self.stroke_band(color, &rungs, style.thickness, closed);
+        }
+    }
+
+    /// Stroke a band `thickness` wide across rungs
+    ///
+    /// The band is wrapped in a fringe when anti-aliased.
+    fn stroke_band(&mut self, color: Color, points: &[Rung], thickness: f32, closed: bool) {
+        if points.len() < 2 {
+            return;
+        }
+
+        let half = thickness * 0.5;
+        let base_index = self.verts.len() as u32;
+        let count = points.len() as u32;
+        let segments = if closed { count } else { count - 1 };
+
+        if !self.anti_alias {
+            let color: [u8; 4] = color.into();
+
+            // an inner and outer vertex for each point
+            self.verts.reserve(points.len() * 2);
+            for rung in points {
+                self.verts.extend(&[
+                    ((rung.point - rung.left * half).into(), OPAQUE_UV, color).into(),
+                    ((rung.point + rung.right * half).into(), OPAQUE_UV, color).into(),
+                ]);
+            }
+
+            self.indicies.reserve(segments as usize * 6);
+            for i in 0..segments {
+                let a = base_index + 2 * i;
+                let b = base_index + 2 * ((i + 1) % count);
+                self.indicies.extend(&[a, a + 1, b, a + 1, b, b + 1]);
+            }
+            return;
+        }
+
+        // the solid core and fringe together are as wide as the line; lines
+        // thinner than the fringe fade instead
+        let core = (half - AA_FRINGE * 0.5).max(0.0);
+        let edge = core + AA_FRINGE;
+        let solid = if thickness < AA_FRINGE {
+            let [_, _, _, alpha]: [u8; 4] = color.into();
+            color.alpha((alpha as f32 * thickness / AA_FRINGE) as u8)
+        } else {
+            color
+        };
+        let (solid, clear): ([u8; 4], [u8; 4]) = (solid.into(), color.alpha(0).into());
+
+        // a clear, solid, solid and clear vertex across the band at each point
+        self.verts.reserve(points.len() * 4);
+        for rung in points {
+            let (point, left, right) = (rung.point, rung.left, rung.right);
+            self.verts.extend(&[
+                ((point - left * edge).into(), OPAQUE_UV, clear).into(),
+                ((point - left * core).into(), OPAQUE_UV, solid).into(),
+                ((point + right * core).into(), OPAQUE_UV, solid).into(),
+                ((point + right * edge).into(), OPAQUE_UV, clear).into(),
+            ]);
+        }
+
+        self.indicies.reserve(segments as usize * 18);
+        for i in 0..segments {
+            let a = base_index + 4 * i;
+            let b = base_index + 4 * ((i + 1) % count);
+            for k in 0..3 {
+                let (a, b) = (a + k, b + k);
+                self.indicies.extend(&[a, a + 1, b, a + 1, b, b + 1]);
+            }
+        }
+    }
+}
+
+/// Offsets which move each point of a ring half of the anti-aliasing fringe
+/// out of the filled area, whichever way the ring winds
+fn fringe_offsets(ring: &[Vec2], hole: bool) -> Vec<Vec2> {
+    let outward = if (signed_area(ring) > 0.0) != hole {
+        -1.0
+    } else {
+        1.0
+    };
+    let edge_normals: Vec<Vec2> = (0..ring.len())
+        .map(|i| (ring[(i + 1) % ring.len()] - ring[i]).unit().normal() * outward)
+        .collect();
+
+    (0..ring.len())
+        .map(|i| {
+            let previous = edge_normals[(i + ring.len() - 1) % ring.len()];
+            miter_offset(previous, edge_normals[i]) * (AA_FRINGE * 0.5)
+        })
+        .collect()
+}
+
+/// Offset of a corner between two edges with unit normals which moves each
+/// edge by one unit, limited so sharp corners do not spike
+fn miter_offset(a: Vec2, b: Vec2) -> Vec2 {
+    let average = (a + b) * 0.5;
+    let len2 = average.len2();
+    if len2 < 0.000_001 {
+        return a;
+    }
+    average * (1.0 / len2).min(4.0)
 }
@@ -0,0 +1,298 @@
+//! Colors which change across filled shapes
+
+use super::tessellate::signed_area;
+use crate::color::Color;
+use crate::math::arc_segments;
+use crate::Vec2;
+
+use std::f32::consts::{PI, TAU};
+
+/// Colors blended across a filled shape
+///
+/// Stops pair an offset with the color at that offset, in order of
+/// increasing offset.  Colors blend between neighbouring stops, and points
+/// before the first stop or after the last take its color.
+///
+/// ```
+/// use immediate_mode::{draw::{DrawData, Gradient}, Color, Rect, Vec2};
+///
+/// # type Vert = ([f32; 2], [f32; 2], [u8; 4]);
+/// let mut draw_data = DrawData::<Vert>::default();
+/// let rect = Rect::new(Vec2::zero(), Vec2::new(100.0, 20.0));
+///
+/// // red to green to blue from left to right
+/// let gradient = Gradient::Linear {
+///     from: rect.min,
+///     to: Vec2::new(rect.max.x, rect.min.y),
+///     stops: vec![
+///         (0.0, Color(0xFF_00_00_FF)),
+///         (0.5, Color(0x00_FF_00_FF)),
+///         (1.0, Color(0x00_00_FF_FF)),
+///     ],
+/// };
+/// draw_data.rect_gradient(rect, &gradient);
+///
+/// // the middle stop splits the rectangle in two
+/// assert_eq!(draw_data.indicies().len(), 4 * 3);
+/// let middle: [u8; 4] = gradient.color_at(rect.center()).into();
+/// assert_eq!(middle, [0, 255, 0, 255]);
+/// ```
+#[derive(Debug, Clone)]
+pub enum Gradient {
+    /// Colors change along the line from `from` to `to` and stay the same
+    /// across it
+    Linear {
+        /// Point at offset `0.0`
+        from: Vec2,
+        /// Point at offset `1.0`
+        to: Vec2,
+        /// Offsets and their colors
+        stops: Vec<(f32, Color)>,
+    },
+    /// Colors change with the distance from a center
+    Radial {
+        /// Point at offset `0.0`
+        center: Vec2,
+        /// Distance from the center at offset `1.0`
+        radius: f32,
+        /// Offsets and their colors
+        stops: Vec<(f32, Color)>,
+    },
+}
+
+impl Gradient {
+    /// Blend from one color to another along a line
+    pub fn linear(from: Vec2, to: Vec2, start: Color, end: Color) -> Self {
+        Gradient::Linear {
+            from,
+            to,
+            stops: vec![(0.0, start), (1.0, end)],
+        }
+    }
+
+    /// Blend from one color at a center to another at a radius
+    pub fn radial(center: Vec2, radius: f32, inner: Color, outer: Color) -> Self {
+        Gradient::Radial {
+            center,
+            radius,
+            stops: vec![(0.0, inner), (1.0, outer)],
+        }
+    }
+
+    /// Color of the gradient at a point
+    ///
+    /// Gradients without stops are transparent.
+    pub fn color_at(&self, point: Vec2) -> Color {
+        let stops = self.stops();
+        let offset = self.offset(point);
+        match stops.iter().position(|&(stop, _)| stop >= offset) {
+            None => stops.last().map_or(Color(0), |&(_, color)| color),
+            Some(0) => stops[0].1,
+            Some(i) => {
+                let ((a, from), (b, to)) = (stops[i - 1], stops[i]);
+                from.lerp(to, (offset - a) / (b - a))
+            }
+        }
+    }
+
+    fn stops(&self) -> &[(f32, Color)] {
+        match self {
+            Gradient::Linear { stops, .. } | Gradient::Radial { stops, .. } => stops,
+        }
+    }
+
+    /// Offset of a point along the gradient
+    fn offset(&self, point: Vec2) -> f32 {
+        match *self {
+            Gradient::Linear { from, to, .. } => {
+                let axis = to - from;
+                if axis.len2() > 0.0 {
+                    (point - from).dot(axis) / axis.len2()
+                } else {
+                    0.0
+                }
+            }
+            Gradient::Radial { center, radius, .. } => {
+                if radius > 0.0 {
+                    (point - center).len() / radius
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+/// Convex pieces of a convex polygon which are cut at the stops of a
+/// gradient, so blending the gradient's color at each corner of a piece
+/// follows the gradient
+pub(crate) fn cells(gradient: &Gradient, polygon: &[Vec2], tolerance: f32) -> Vec<Vec<Vec2>> {
+    let offsets: Vec<f32> = polygon.iter().map(|&p| gradient.offset(p)).collect();
+    let lowest = offsets.iter().copied().fold(f32::INFINITY, f32::min);
+    let highest = offsets.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+
+    // every band between stops which crosses the polygon; the nearest point
+    // to a radial gradient's center may be between corners
+    let lowest = lowest.min(0.0);
+    let mut cuts = vec![lowest];
+    cuts.extend(
+        gradient
+            .stops()
+            .iter()
+            .map(|&(offset, _)| offset)
+            .filter(|&offset| offset > lowest && offset < highest),
+    );
+    cuts.push(highest);
+
+    let mut cells = Vec::new();
+    match *gradient {
+        Gradient::Linear { .. } => {
+            for band in cuts.windows(2) {
+                let (low, high) = (band[0], band[1]);
+                let cell = clip(polygon, |p| gradient.offset(p) - low);
+                let cell = clip(&cell, |p| high - gradient.offset(p));
+                cells.push(cell);
+            }
+        }
+        Gradient::Radial { radius, .. } if radius <= 0.0 => cells.push(polygon.to_vec()),
+        Gradient::Radial { center, radius, .. } => {
+            // rings of sectors which share the points of their edges; the
+            // outer ring reaches past the polygon's farthest corner
+            let far = highest * radius;
+            let segments = arc_segments(far, TAU, tolerance).max(3);
+            let reach = 1.0 / (PI / segments as f32).cos();
+            let ring = |offset: f32, i: usize| {
+                let (sin, cos) = (TAU * i as f32 / segments as f32).sin_cos();
+                center + Vec2::new(cos, sin) * (offset * radius)
+            };
+
+            let (min, max) = bounds(polygon);
+            for (i, band) in cuts.windows(2).enumerate() {
+                let inner = band[0].max(0.0);
+                let outer = if i + 2 == cuts.len() {
+                    band[1] * reach + 0.001
+                } else {
+                    band[1]
+                };
+                for k in 0..segments {
+                    let sector = [
+                        ring(inner, k),
+                        ring(outer, k),
+                        ring(outer, k + 1),
+                        ring(inner, k + 1),
+                    ];
+                    let (low, high) = bounds(&sector);
+                    if high.x < min.x || high.y < min.y || low.x > max.x || low.y > max.y {
+                        continue;
+                    }
+                    cells.push(clip_convex(&sector, polygon));
+                }
+            }
+        }
+    }
+
+    cells.retain(|cell| cell.len() >= 3);
+    cells
+}
+
+/// Corners of the box around points
+fn bounds(points: &[Vec2]) -> (Vec2, Vec2) {
+    points.iter().fold(
+        (
+            Vec2::new(f32::INFINITY, f32::INFINITY),
+            Vec2::new(f32::NEG_INFINITY, f32::NEG_INFINITY),
+        ),
+        |(min, max), p| {
+            (
+                Vec2::new(min.x.min(p.x), min.y.min(p.y)),
+                Vec2::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        },
+    )
+}
+
+/// Part of a convex polygon inside of another convex polygon
+fn clip_convex(polygon: &[Vec2], by: &[Vec2]) -> Vec<Vec2> {
+    let winding = signed_area(by).signum();
+    let mut clipped = polygon.to_vec();
+    for i in 0..by.len() {
+        let (a, b) = (by[i], by[(i + 1) % by.len()]);
+        let edge = b - a;
+        clipped = clip(&clipped, |p| {
+            let to = p - a;
+            (edge.x * to.y - edge.y * to.x) * winding
+        });
+    }
+    clipped
+}
+
+/// Part of a polygon where `inside` is not negative
+///
+/// `inside` must change linearly along each edge, like the distance from a
+/// line.
+fn clip<F: Fn(Vec2) -> f32>(polygon: &[Vec2], inside: F) -> Vec<Vec2> {
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+        let (da, db) = (inside(a), inside(b));
+        if da >= 0.0 {
+            clipped.push(a);
+        }
+        if (da >= 0.0) != (db >= 0.0) {
+            clipped.push(a + (b - a) * (da / (da - db)));
+        }
+    }
+    clipped.dedup();
+    clipped
+}
+
+#[cfg(test)]
+mod test {
+    use super::{cells, signed_area, Gradient};
+    use crate::color::Color;
+    use crate::Vec2;
+
+    const BLACK: Color = Color(0x00_00_00_FF);
+    const WHITE: Color = Color(0xFF_FF_FF_FF);
+
+    fn square() -> Vec<Vec2> {
+        vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ]
+    }
+
+    fn area(cells: &[Vec<Vec2>]) -> f32 {
+        cells.iter().map(|cell| signed_area(cell).abs() * 0.5).sum()
+    }
+
+    #[test]
+    fn linear_cells_are_cut_at_stops() {
+        let gradient = Gradient::Linear {
+            from: Vec2::new(0.0, 0.0),
+            to: Vec2::new(10.0, 0.0),
+            stops: vec![(0.0, BLACK), (0.3, WHITE), (0.6, BLACK), (1.0, WHITE)],
+        };
+        let cells = cells(&gradient, &square(), 0.25);
+        assert_eq!(cells.len(), 3);
+        assert!((area(&cells) - 100.0).abs() < 0.001);
+
+        let cut: [u8; 4] = gradient.color_at(Vec2::new(3.0, 5.0)).into();
+        assert_eq!(cut, [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn radial_cells_cover_the_polygon() {
+        // centered on a corner, so rings only cover part of the square
+        let gradient = Gradient::radial(Vec2::new(0.0, 0.0), 5.0, WHITE, BLACK);
+        let cells = cells(&gradient, &square(), 0.25);
+        assert!((area(&cells) - 100.0).abs() < 0.01);
+
+        let edge: [u8; 4] = gradient.color_at(Vec2::new(3.0, 4.0)).into();
+        assert_eq!(edge, [0, 0, 0, 255]);
+        let outside: [u8; 4] = gradient.color_at(Vec2::new(10.0, 10.0)).into();
+        assert_eq!(outside, [0, 0, 0, 255]);
+    }
+}
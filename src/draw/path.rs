@@ -0,0 +1,391 @@
+//! Shapes built from lines and curves
+
+use super::tessellate::signed_area;
+use crate::math::arc_segments;
+use crate::{Bezier, Vec2};
+
+use std::f32::consts::{PI, TAU};
+
+/// How overlapping parts of a filled path decide what is inside
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillRule {
+    /// Inside wherever the path winds around more times one way than the
+    /// other, so shapes wound the same way merge and opposite windings cut
+    /// holes
+    NonZero,
+    /// Inside wherever the path surrounds an odd number of times, so every
+    /// nested shape cuts a hole
+    EvenOdd,
+}
+
+impl FillRule {
+    fn is_inside(self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+/// A straight or curved piece of a path ending at its last point
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Segment {
+    Line(Vec2),
+    Quad(Vec2, Vec2),
+    Cubic(Vec2, Vec2, Vec2),
+    Arc {
+        center: Vec2,
+        radius: f32,
+        start: f32,
+        sweep: f32,
+    },
+}
+
+/// A run of connected segments
+#[derive(Debug, Clone, PartialEq)]
+struct SubPath {
+    start: Vec2,
+    segments: Vec<Segment>,
+    closed: bool,
+}
+
+/// Outline of a shape made of lines and curves
+///
+/// A path is built from commands which each continue from where the last
+/// ended; `move_to` lifts the pen to start a separate piece.  Curves are
+/// flattened when the path is drawn, so the same path can be drawn at any
+/// size.
+///
+/// ```
+/// use immediate_mode::{draw::{DrawData, FillRule, Path}, Color, Vec2};
+///
+/// # type Vert = ([f32; 2], [f32; 2], [u8; 4]);
+/// let mut draw_data = DrawData::<Vert>::default();
+/// let white = Color(0xFF_FF_FF_FF);
+///
+/// // a play button with a rounded tip
+/// let play = Path::new()
+///     .move_to(Vec2::new(0.0, 0.0))
+///     .arc_to(Vec2::new(20.0, 10.0), Vec2::new(0.0, 20.0), 3.0)
+///     .line_to(Vec2::new(0.0, 20.0))
+///     .close();
+/// draw_data.path_fill(white, &play, FillRule::NonZero);
+/// assert!(draw_data.indicies().len() > 3);
+///
+/// // a check mark
+/// let check = Path::new()
+///     .move_to(Vec2::new(0.0, 10.0))
+///     .line_to(Vec2::new(6.0, 16.0))
+///     .line_to(Vec2::new(20.0, 2.0));
+/// draw_data.path_stroke(white, 2.0, &check);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Path {
+    subpaths: Vec<SubPath>,
+}
+
+impl Path {
+    /// Create an empty path
+    pub fn new() -> Self {
+        Path::default()
+    }
+
+    /// Start a new piece of the path at a point
+    pub fn move_to(mut self, point: Vec2) -> Self {
+        self.subpaths.push(SubPath {
+            start: point,
+            segments: Vec::new(),
+            closed: false,
+        });
+        self
+    }
+
+    /// Draw a straight line to a point
+    pub fn line_to(self, point: Vec2) -> Self {
+        self.push(point, Segment::Line(point))
+    }
+
+    /// Draw a quadratic Bézier curve bending toward `ctrl`
+    pub fn quad_to(self, ctrl: Vec2, to: Vec2) -> Self {
+        self.push(ctrl, Segment::Quad(ctrl, to))
+    }
+
+    /// Draw a cubic Bézier curve bending toward `ctrl1` then `ctrl2`
+    pub fn cubic_to(self, ctrl1: Vec2, ctrl2: Vec2, to: Vec2) -> Self {
+        self.push(ctrl1, Segment::Cubic(ctrl1, ctrl2, to))
+    }
+
+    /// Round the corner at `corner` between lines toward it and on toward
+    /// `to`
+    ///
+    /// Draws a line toward the corner and an arc of `radius` which touches
+    /// both lines, ending where it meets the line to `to`.  Continue with
+    /// `line_to(to)` to draw the rest of that line.
+    pub fn arc_to(self, corner: Vec2, to: Vec2, radius: f32) -> Self {
+        let from = match self.current() {
+            Some(from) => from,
+            None => return self.move_to(corner),
+        };
+
+        let (d0, d1) = ((from - corner).unit(), (to - corner).unit());
+        let cos = d0.dot(d1).clamp(-1.0, 1.0);
+        let half_angle = cos.acos() * 0.5;
+        if radius <= 0.0 || half_angle.tan().abs() < 0.000_1 || cos <= -0.999_9 {
+            // a straight or folded corner has nothing to round
+            return self.line_to(corner);
+        }
+
+        // the circle sits on the bisector, touching each line
+        let tangent = radius / half_angle.tan();
+        let center = corner + (d0 + d1).unit() * (radius / half_angle.sin());
+        let (a, b) = (corner + d0 * tangent, corner + d1 * tangent);
+        let angle = |p: Vec2| (p.y - center.y).atan2(p.x - center.x);
+        let start = angle(a);
+        let sweep = (angle(b) - start + PI).rem_euclid(TAU) - PI;
+
+        self.line_to(a).push(
+            a,
+            Segment::Arc {
+                center,
+                radius,
+                start,
+                sweep,
+            },
+        )
+    }
+
+    /// Join the current piece back to its start
+    ///
+    /// Commands after closing start a new piece at the same point.
+    pub fn close(mut self) -> Self {
+        if let Some(subpath) = self.subpaths.last_mut() {
+            subpath.closed = true;
+        }
+        self
+    }
+
+    /// Where the next command starts from
+    fn current(&self) -> Option<Vec2> {
+        self.subpaths.last().map(|subpath| {
+            if subpath.closed {
+                return subpath.start;
+            }
+            match subpath.segments.last() {
+                None => subpath.start,
+                Some(&Segment::Line(to))
+                | Some(&Segment::Quad(_, to))
+                | Some(&Segment::Cubic(_, _, to)) => to,
+                Some(&Segment::Arc {
+                    center,
+                    radius,
+                    start,
+                    sweep,
+                }) => {
+                    let (sin, cos) = (start + sweep).sin_cos();
+                    center + Vec2::new(cos, sin) * radius
+                }
+            }
+        })
+    }
+
+    /// Add a segment, starting at `start` when there is nowhere to continue
+    /// from
+    fn push(mut self, start: Vec2, segment: Segment) -> Self {
+        match self.subpaths.last() {
+            None => self = self.move_to(start),
+            Some(subpath) if subpath.closed => {
+                let from = subpath.start;
+                self = self.move_to(from);
+            }
+            Some(_) => {}
+        }
+        if let Some(subpath) = self.subpaths.last_mut() {
+            subpath.segments.push(segment);
+        }
+        self
+    }
+
+    /// Points along each piece of the path and whether it is closed
+    ///
+    /// Curves stray at most `tolerance` from their segments.
+    pub(crate) fn flatten(&self, tolerance: f32) -> Vec<(Vec<Vec2>, bool)> {
+        self.subpaths
+            .iter()
+            .map(|subpath| {
+                let mut points = vec![subpath.start];
+                for &segment in &subpath.segments {
+                    let from = *points.last().unwrap();
+                    match segment {
+                        Segment::Line(to) => points.push(to),
+                        Segment::Quad(ctrl, to) => {
+                            let curve = Bezier::Quadratic { from, ctrl, to };
+                            points.extend(curve.flatten(tolerance).skip(1));
+                        }
+                        Segment::Cubic(ctrl1, ctrl2, to) => {
+                            let curve = Bezier::Cubic {
+                                from,
+                                ctrl1,
+                                ctrl2,
+                                to,
+                            };
+                            points.extend(curve.flatten(tolerance).skip(1));
+                        }
+                        Segment::Arc {
+                            center,
+                            radius,
+                            start,
+                            sweep,
+                        } => {
+                            let segments = arc_segments(radius, sweep, tolerance);
+                            points.extend((1..=segments).map(|i| {
+                                let angle = start + sweep * i as f32 / segments as f32;
+                                let (sin, cos) = angle.sin_cos();
+                                center + Vec2::new(cos, sin) * radius
+                            }));
+                        }
+                    }
+                }
+                points.dedup();
+                if subpath.closed && points.len() > 1 && points.first() == points.last() {
+                    points.pop();
+                }
+                (points, subpath.closed)
+            })
+            .collect()
+    }
+}
+
+/// Group rings which do not cross each other into outlines and the holes
+/// inside of each outline, following a fill rule
+///
+/// Rings which neither start nor end the filled area, like a shape wound
+/// the same way inside of another under `FillRule::NonZero`, are dropped.
+pub(crate) fn fill_regions(rings: &[Vec<Vec2>], rule: FillRule) -> Vec<(usize, Vec<usize>)> {
+    let mut outlines = Vec::new();
+    let mut holes = Vec::new();
+    for (r, ring) in rings.iter().enumerate() {
+        let area = signed_area(ring);
+        if area == 0.0 {
+            continue;
+        }
+
+        // compare either side of the ring at the middle of its first edge
+        let middle = (ring[0] + ring[1]) * 0.5;
+        let outside: i32 = rings
+            .iter()
+            .enumerate()
+            .filter(|&(other, _)| other != r)
+            .map(|(_, other)| winding(other, middle))
+            .sum();
+        let inside = outside + if area > 0.0 { 1 } else { -1 };
+
+        match (rule.is_inside(inside), rule.is_inside(outside)) {
+            (true, false) => outlines.push(r),
+            (false, true) => holes.push(r),
+            _ => {}
+        }
+    }
+
+    let mut regions: Vec<(usize, Vec<usize>)> = outlines
+        .iter()
+        .map(|&outline| (outline, Vec::new()))
+        .collect();
+    for hole in holes {
+        // the smallest outline around the hole
+        let point = rings[hole][0];
+        let region = regions
+            .iter_mut()
+            .filter(|(outline, _)| winding(&rings[*outline], point) != 0)
+            .min_by(|(a, _), (b, _)| {
+                let a = signed_area(&rings[*a]).abs();
+                let b = signed_area(&rings[*b]).abs();
+                a.total_cmp(&b)
+            });
+        if let Some((_, holes)) = region {
+            holes.push(hole);
+        }
+    }
+    regions
+}
+
+/// Number of times a ring winds around a point, counting turns with
+/// positive area as positive
+fn winding(ring: &[Vec2], point: Vec2) -> i32 {
+    let mut winding = 0;
+    for i in 0..ring.len() {
+        let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+        let side = (b.x - a.x) * (point.y - a.y) - (point.x - a.x) * (b.y - a.y);
+        if a.y <= point.y && b.y > point.y && side > 0.0 {
+            winding += 1;
+        } else if a.y > point.y && b.y <= point.y && side < 0.0 {
+            winding -= 1;
+        }
+    }
+    winding
+}
+
+#[cfg(test)]
+mod test {
+    use super::{fill_regions, FillRule, Path};
+    use crate::Vec2;
+
+    fn square(size: f32) -> Vec<Vec2> {
+        vec![
+            Vec2::new(-size, -size),
+            Vec2::new(size, -size),
+            Vec2::new(size, size),
+            Vec2::new(-size, size),
+        ]
+    }
+
+    #[test]
+    fn fill_rules_pick_holes() {
+        // nested squares wound the same way
+        let rings = vec![square(10.0), square(5.0), square(2.0)];
+        assert_eq!(fill_regions(&rings, FillRule::NonZero), vec![(0, vec![])]);
+        assert_eq!(
+            fill_regions(&rings, FillRule::EvenOdd),
+            vec![(0, vec![1]), (2, vec![])]
+        );
+
+        // wound the other way, the inner square cuts a hole either way
+        let mut inner = square(5.0);
+        inner.reverse();
+        let rings = vec![square(10.0), inner];
+        assert_eq!(fill_regions(&rings, FillRule::NonZero), vec![(0, vec![1])]);
+    }
+
+    #[test]
+    fn non_finite_points_do_not_panic() {
+        let mut broken = square(20.0);
+        broken.insert(1, Vec2::new(f32::NAN, f32::NAN));
+        let rings = vec![broken, square(10.0), square(5.0), square(2.0)];
+        fill_regions(&rings, FillRule::EvenOdd);
+        fill_regions(&rings, FillRule::NonZero);
+    }
+
+    #[test]
+    fn commands_continue_from_the_last_point() {
+        let path = Path::new()
+            .move_to(Vec2::new(0.0, 0.0))
+            .line_to(Vec2::new(10.0, 0.0))
+            .arc_to(Vec2::new(20.0, 0.0), Vec2::new(20.0, 10.0), 5.0)
+            .line_to(Vec2::new(20.0, 10.0))
+            .close()
+            .line_to(Vec2::new(0.0, 10.0));
+
+        let pieces = path.flatten(0.25);
+        assert_eq!(pieces.len(), 2);
+
+        // the arc starts and ends where it touches each line
+        let (points, closed) = &pieces[0];
+        assert!(closed);
+        assert!(points.contains(&Vec2::new(15.0, 0.0)));
+        assert!(points
+            .iter()
+            .any(|p| (p.x - 20.0).abs() < 0.001 && (p.y - 5.0).abs() < 0.001));
+
+        // closing moves back to the start
+        assert_eq!(pieces[1].0, vec![Vec2::new(0.0, 0.0), Vec2::new(0.0, 10.0)]);
+    }
+}
@@ -0,0 +1,511 @@
+//! Outlines of strokes with joins and caps
+
+use crate::math::arc_segments;
+use crate::Vec2;
+
+use std::f32::consts::FRAC_PI_2;
+
+/// Shortest length in pixels of a dash or gap which is not zero
+const MIN_DASH: f32 = 0.01;
+
+/// Shape of the corner where two segments of a stroke meet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineJoin {
+    /// Extend the edges until they meet, falling back to `Bevel` past the
+    /// miter limit
+    Miter,
+    /// Cut the corner off straight
+    Bevel,
+    /// Round the corner
+    Round,
+}
+
+/// Shape of the ends of an open stroke
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineCap {
+    /// End flat at the end points
+    Butt,
+    /// End flat half of the thickness past the end points
+    Square,
+    /// End in a half circle around the end points
+    Round,
+}
+
+/// How to stroke lines and outlines
+///
+/// A thickness alone converts into a solid style with the default joins and
+/// caps.  Dash patterns are borrowed, so styles are cheap to copy.
+///
+/// ```
+/// use immediate_mode::draw::{LineCap, LineJoin, StrokeStyle};
+///
+/// let style = StrokeStyle {
+///     join: LineJoin::Round,
+///     cap: LineCap::Round,
+///     ..StrokeStyle::from(2.0)
+/// };
+/// assert_eq!(style.thickness, 2.0);
+///
+/// // marching ants around a selection
+/// # let time = 0.5;
+/// let marquee = StrokeStyle {
+///     dash: &[4.0, 4.0],
+///     dash_phase: time * 16.0,
+///     ..StrokeStyle::from(1.0)
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StrokeStyle<'a> {
+    /// Width of the stroke in pixels
+    pub thickness: f32,
+    /// Corners between segments
+    pub join: LineJoin,
+    /// Ends of open strokes
+    pub cap: LineCap,
+    /// Longest miter, in multiples of the thickness, before `LineJoin::Miter`
+    /// is beveled instead
+    pub miter_limit: f32,
+    /// Alternating lengths of dashes and the gaps between them, starting
+    /// with a dash; empty for a solid stroke
+    ///
+    /// Patterns with an odd number of lengths repeat twice, so dashes and
+    /// gaps trade places the second time around.  Each dash is capped, so
+    /// dashes with no length and round caps draw dots.  Other lengths are at
+    /// least the draw tolerance, which limits how many dashes a stroke has.
+    pub dash: &'a [f32],
+    /// Distance into the dash pattern where the stroke starts
+    ///
+    /// Increasing the phase over time moves the dashes backward along the
+    /// stroke, for example to animate a selection marquee.
+    pub dash_phase: f32,
+}
+
+impl Default for StrokeStyle<'_> {
+    fn default() -> Self {
+        StrokeStyle {
+            thickness: 1.0,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            miter_limit: 4.0,
+            dash: &[],
+            dash_phase: 0.0,
+        }
+    }
+}
+
+impl From<f32> for StrokeStyle<'_> {
+    fn from(thickness: f32) -> Self {
+        StrokeStyle {
+            thickness,
+            ..StrokeStyle::default()
+        }
+    }
+}
+
+/// A pair of verticies across a stroke
+///
+/// For a distance `w` from the center of the stroke, the verticies are at
+/// `point - left * w` and `point + right * w`, so a single rung describes
+/// both the edge of the stroke and the edge of an anti-aliasing fringe.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Rung {
+    pub point: Vec2,
+    pub left: Vec2,
+    pub right: Vec2,
+}
+
+impl Rung {
+    fn new(point: Vec2, left: Vec2, right: Vec2) -> Self {
+        Rung { point, left, right }
+    }
+}
+
+/// Rungs along a stroke through points, including its joins and caps
+///
+/// `tolerance` sets how closely round joins and caps follow their arcs, and
+/// joins which turn so little that their miter stays within it are never
+/// beveled or rounded.
+pub(crate) fn rungs(
+    points: &[Vec2],
+    style: &StrokeStyle<'_>,
+    closed: bool,
+    tolerance: f32,
+) -> Vec<Rung> {
+    // repeated points have no direction
+    let mut points = points.to_vec();
+    points.dedup();
+    if closed && points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    if points.len() < 2 {
+        return Vec::new();
+    }
+
+    let half = style.thickness * 0.5;
+    let count = points.len();
+    let direction = |i: usize| (points[(i + 1) % count] - points[i]).unit();
+    let mut rungs = Vec::with_capacity(count + 4);
+
+    if !closed {
+        cap(
+            &mut rungs,
+            points[0],
+            direction(0),
+            style,
+            half,
+            tolerance,
+            true,
+        );
+    }
+
+    let joins = if closed { 0..count } else { 1..count - 1 };
+    for i in joins {
+        let d0 = direction((i + count - 1) % count);
+        let d1 = direction(i);
+        join(&mut rungs, points[i], d0, d1, style, half, tolerance);
+    }
+
+    if !closed {
+        let last = count - 1;
+        let d = direction(last - 1);
+        cap(&mut rungs, points[last], d, style, half, tolerance, false);
+    }
+
+    rungs
+}
+
+/// Points of each dash along a stroke through points and whether the dash
+/// is closed
+///
+/// The pattern is measured along the whole length of the stroke, so dashes
+/// continue around corners; on closed strokes a dash crossing the first
+/// point is joined into one, and a dash covering the whole stroke stays
+/// closed.  Lengths other than zero are raised to the tolerance, or to
+/// `MIN_DASH` if it is smaller.
+pub(crate) fn dashes(
+    points: &[Vec2],
+    closed: bool,
+    pattern: &[f32],
+    phase: f32,
+    tolerance: f32,
+) -> Vec<(Vec<Vec2>, bool)> {
+    let shortest = tolerance.max(MIN_DASH);
+    let mut pattern = pattern
+        .iter()
+        .map(|&length| {
+            if length > 0.0 {
+                length.max(shortest)
+            } else {
+                0.0
+            }
+        })
+        .collect::<Vec<_>>();
+    if pattern.len() % 2 == 1 {
+        pattern.extend_from_within(..);
+    }
+    let total: f32 = pattern.iter().sum();
+    if total <= 0.0 || !total.is_finite() {
+        return vec![(points.to_vec(), closed)];
+    }
+
+    // find the part of the pattern where the stroke starts, keeping dashes
+    // with no length which start right at the first point
+    let mut entry = 0;
+    let mut remaining = phase.rem_euclid(total);
+    while remaining > 0.0 && remaining >= pattern[entry] {
+        remaining -= pattern[entry];
+        entry = (entry + 1) % pattern.len();
+    }
+    remaining = pattern[entry] - remaining;
+
+    let ends = if closed {
+        points.len()
+    } else {
+        points.len().saturating_sub(1)
+    };
+    let mut dashes = Vec::new();
+    let mut dash = Vec::new();
+    if entry % 2 == 0 {
+        dash.extend(points.first());
+    }
+    let starts_on = entry % 2 == 0;
+
+    for i in 0..ends {
+        let (a, b) = (points[i], points[(i + 1) % points.len()]);
+        let length = (b - a).len();
+        let direction = (b - a).unit();
+
+        let mut position = 0.0;
+        while length - position > remaining {
+            position += remaining;
+            let point = a + direction * position;
+            if entry % 2 == 0 {
+                dash.push(point);
+                dash.dedup();
+                // give dashes with no length a direction for their caps
+                if dash.len() == 1 {
+                    dash.push(point + direction * 0.001);
+                }
+                dashes.push((std::mem::take(&mut dash), false));
+            } else {
+                dash.push(point);
+            }
+            entry = (entry + 1) % pattern.len();
+            remaining = pattern[entry];
+        }
+
+        remaining -= length - position;
+        if entry % 2 == 0 {
+            dash.push(b);
+        }
+    }
+
+    if entry % 2 == 0 && dash.len() > 1 {
+        if closed && starts_on && dashes.is_empty() {
+            // the dash never ended, so it goes all the way around
+            return vec![(points.to_vec(), true)];
+        }
+        if closed && starts_on {
+            // the last dash runs into the first
+            dash.extend(dashes.remove(0).0.into_iter().skip(1));
+        }
+        dashes.push((dash, false));
+    }
+    dashes
+}
+
+/// Rungs at an end of an open stroke
+fn cap(
+    rungs: &mut Vec<Rung>,
+    point: Vec2,
+    d: Vec2,
+    style: &StrokeStyle<'_>,
+    half: f32,
+    tolerance: f32,
+    start: bool,
+) {
+    let n = d.normal();
+    // points away from the stroke
+    let out = if start { d * -1.0 } else { d };
+
+    match style.cap {
+        LineCap::Butt => rungs.push(Rung::new(point, n, n)),
+        LineCap::Square => rungs.push(Rung::new(point, n - out, n + out)),
+        LineCap::Round => {
+            // pairs of points mirrored across the stroke, from the tip of the
+            // half circle at the start or to it at the end
+            let segments = arc_segments(half, FRAC_PI_2, tolerance);
+            for k in 0..=segments {
+                let k = if start { segments - k } else { k };
+                let (sin, cos) = (FRAC_PI_2 * k as f32 / segments as f32).sin_cos();
+                rungs.push(Rung::new(point, n * cos - out * sin, n * cos + out * sin));
+            }
+        }
+    }
+}
+
+/// Rungs at the corner between segments going in directions `d0` and `d1`
+fn join(
+    rungs: &mut Vec<Rung>,
+    point: Vec2,
+    d0: Vec2,
+    d1: Vec2,
+    style: &StrokeStyle<'_>,
+    half: f32,
+    tolerance: f32,
+) {
+    let (n0, n1) = (d0.normal(), d1.normal());
+
+    // the miter reaches the corner of both edges one unit from the center
+    let average = (n0 + n1) * 0.5;
+    let miter = if average.len2() > 0.000_001 {
+        average * (1.0 / average.len2())
+    } else {
+        // the stroke doubles back on itself
+        d0 * f32::INFINITY
+    };
+    let miter_len = miter.len();
+
+    let barely_turns = (miter_len - 1.0) * half <= tolerance;
+    if barely_turns || (style.join == LineJoin::Miter && miter_len <= style.miter_limit) {
+        rungs.push(Rung::new(point, miter, miter));
+        return;
+    }
+
+    // keep the inner corner from reaching past short segments
+    let inner = if miter_len.is_finite() {
+        miter * (style.miter_limit.min(miter_len) / miter_len)
+    } else {
+        n0 * 0.0
+    };
+
+    // turning toward the normal puts the outer edge on the left, and the
+    // miter always leans toward the normal
+    let cross = d0.x * d1.y - d0.y * d1.x;
+    let turn = cross.atan2(d0.dot(d1));
+    let rung = |outer: Vec2| {
+        if cross > 0.0 {
+            Rung::new(point, outer, inner)
+        } else {
+            Rung::new(point, inner, outer)
+        }
+    };
+
+    match style.join {
+        LineJoin::Miter | LineJoin::Bevel => {
+            rungs.push(rung(n0));
+            rungs.push(rung(n1));
+        }
+        LineJoin::Round => {
+            // sweep the first normal around to the second
+            let segments = arc_segments(half, turn, tolerance);
+            for k in 0..=segments {
+                let (sin, cos) = (turn * k as f32 / segments as f32).sin_cos();
+                let outer = Vec2::new(n0.x * cos - n0.y * sin, n0.x * sin + n0.y * cos);
+                rungs.push(rung(outer));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{rungs, LineCap, LineJoin, StrokeStyle};
+    use crate::Vec2;
+
+    #[test]
+    fn sharp_miters_are_limited() {
+        // a spike which nearly doubles back
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 0.0),
+            Vec2::new(0.0, 1.0),
+        ];
+        let style = StrokeStyle::from(4.0);
+        let rungs = rungs(&points, &style, false, 0.25);
+
+        // the join is beveled into two rungs and nothing reaches past the
+        // miter limit
+        assert_eq!(rungs.len(), 4);
+        for rung in &rungs {
+            assert!(rung.left.len() <= style.miter_limit + 0.001);
+            assert!(rung.right.len() <= style.miter_limit + 0.001);
+        }
+    }
+
+    #[test]
+    fn dashes_continue_around_corners() {
+        use super::dashes;
+
+        // a corner 10 units along a 20 unit path
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 10.0),
+        ];
+        let dashes = dashes(&points, false, &[6.0, 2.0], 0.0, 0.25);
+        assert_eq!(dashes.len(), 3);
+        assert_eq!(dashes[0].0, vec![Vec2::new(0.0, 0.0), Vec2::new(6.0, 0.0)]);
+        assert_eq!(
+            dashes[1].0,
+            vec![
+                Vec2::new(8.0, 0.0),
+                Vec2::new(10.0, 0.0),
+                Vec2::new(10.0, 4.0)
+            ]
+        );
+        assert_eq!(
+            dashes[2].0,
+            vec![Vec2::new(10.0, 6.0), Vec2::new(10.0, 10.0)]
+        );
+    }
+
+    #[test]
+    fn dash_phase_shifts_the_pattern() {
+        use super::dashes;
+
+        let points = [Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0)];
+        let shifted = dashes(&points, false, &[4.0], 1.0, 0.25);
+        assert_eq!(shifted[0].0, vec![Vec2::new(0.0, 0.0), Vec2::new(3.0, 0.0)]);
+        assert_eq!(
+            shifted[1].0,
+            vec![Vec2::new(7.0, 0.0), Vec2::new(10.0, 0.0)]
+        );
+
+        // a closed square joins the dash crossing its first corner
+        let square = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ];
+        let joined = dashes(&square, true, &[5.0, 5.0], 2.0, 0.25);
+        assert_eq!(joined.len(), 4);
+        assert_eq!(joined[3].0[0], Vec2::new(0.0, 2.0));
+        assert_eq!(*joined[3].0.last().unwrap(), Vec2::new(3.0, 0.0));
+
+        // a dash longer than the square goes all the way around
+        let around = dashes(&square, true, &[50.0, 5.0], 0.0, 0.25);
+        assert_eq!(around, vec![(square.to_vec(), true)]);
+    }
+
+    #[test]
+    fn dots_start_at_the_first_point() {
+        use super::dashes;
+
+        let square = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ];
+        let dots = dashes(&square, true, &[0.0, 5.0], 0.0, 0.25);
+
+        // two dots along each side, one of them on each corner
+        assert_eq!(dots.len(), 8);
+        for (i, (dot, closed)) in dots.iter().enumerate() {
+            assert!(!closed);
+            assert_eq!(dot.len(), 2);
+            assert!((dot[1] - dot[0]).len() < 0.01);
+            if i % 2 == 0 {
+                assert_eq!(dot[0], square[i / 2]);
+            }
+        }
+    }
+
+    #[test]
+    fn short_dashes_are_lengthened() {
+        use super::dashes;
+
+        let points = [Vec2::new(0.0, 0.0), Vec2::new(100.0, 0.0)];
+        let dashes = dashes(&points, false, &[1e-3, 1e-3], 0.0, 0.25);
+        assert_eq!(dashes.len(), 200);
+    }
+
+    #[test]
+    fn caps_and_round_joins_add_rungs() {
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(50.0, 0.0),
+            Vec2::new(50.0, 50.0),
+        ];
+        let butt = rungs(&points, &StrokeStyle::from(10.0), false, 0.25);
+        let round = StrokeStyle {
+            join: LineJoin::Round,
+            cap: LineCap::Round,
+            ..StrokeStyle::from(10.0)
+        };
+        let round = rungs(&points, &round, false, 0.25);
+        assert_eq!(butt.len(), 3);
+        assert!(round.len() > 6);
+
+        // a closed square has a miter at each corner
+        let square = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ];
+        assert_eq!(rungs(&square, &StrokeStyle::default(), true, 0.25).len(), 4);
+    }
+}
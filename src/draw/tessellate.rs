@@ -0,0 +1,261 @@
+//! Splitting filled polygons into triangles
+
+use crate::Vec2;
+
+/// Twice the signed area of a ring, positive when it turns counter-clockwise
+/// with y pointing up
+pub(crate) fn signed_area(ring: &[Vec2]) -> f32 {
+    (0..ring.len())
+        .map(|i| {
+            let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum()
+}
+
+/// Whether every corner of a ring turns the same way
+pub(crate) fn is_convex(ring: &[Vec2]) -> bool {
+    let count = ring.len();
+    let mut sign = 0.0;
+    for i in 0..count {
+        let (a, b, c) = (ring[i], ring[(i + 1) % count], ring[(i + 2) % count]);
+        let turn = cross(b - a, c - b);
+        if turn == 0.0 {
+            continue;
+        }
+        if sign * turn < 0.0 {
+            return false;
+        }
+        sign = turn;
+    }
+    true
+}
+
+/// Triangles covering a polygon and its holes
+///
+/// Indicies refer to the points of the outline followed by the points of
+/// each hole in order.  Rings must not intersect themselves or each other,
+/// and holes must be inside of the outline with at least three points; both
+/// may wind either way.
+pub(crate) fn triangulate(outline: &[Vec2], holes: &[&[Vec2]]) -> Vec<u32> {
+    let mut points = outline.to_vec();
+    let mut polygon: Vec<u32> = (0..outline.len() as u32).collect();
+    if signed_area(outline) < 0.0 {
+        polygon.reverse();
+    }
+
+    // holes wind the other way and are joined to the outline by a bridge
+    // to their rightmost point, rightmost holes first so bridges can reach
+    // through holes which are already joined
+    let mut rings = Vec::with_capacity(holes.len());
+    for hole in holes {
+        let first = points.len() as u32;
+        points.extend_from_slice(hole);
+        let mut ring: Vec<u32> = (first..points.len() as u32).collect();
+        if signed_area(hole) > 0.0 {
+            ring.reverse();
+        }
+        rings.push(ring);
+    }
+    let rightmost = |ring: &[u32]| {
+        (0..ring.len())
+            .max_by(|&a, &b| {
+                let (a, b) = (points[ring[a] as usize], points[ring[b] as usize]);
+                a.x.total_cmp(&b.x).then(b.y.total_cmp(&a.y))
+            })
+            .unwrap()
+    };
+    rings.sort_by(|a, b| {
+        let (a, b) = (
+            points[a[rightmost(a)] as usize],
+            points[b[rightmost(b)] as usize],
+        );
+        b.x.total_cmp(&a.x)
+    });
+
+    for (i, ring) in rings.iter().enumerate() {
+        let start = rightmost(ring);
+        let from = points[ring[start] as usize];
+        let later = &rings[i + 1..];
+        let to = bridge(&points, &polygon, later, from);
+
+        // walk around the hole and back across the bridge
+        let mut joined = Vec::with_capacity(ring.len() + 2);
+        joined.extend(ring[start..].iter().chain(&ring[..start]));
+        joined.push(ring[start]);
+        joined.push(polygon[to]);
+        polygon.splice(to + 1..to + 1, joined);
+    }
+
+    clip_ears(&points, polygon)
+}
+
+/// Index in `polygon` of the nearest point which can be joined to a point
+/// inside of it without crossing any edges
+fn bridge(points: &[Vec2], polygon: &[u32], holes: &[Vec<u32>], from: Vec2) -> usize {
+    let edges = || {
+        let rings = Some(polygon)
+            .into_iter()
+            .chain(holes.iter().map(Vec::as_slice));
+        rings.flat_map(move |ring| {
+            (0..ring.len()).map(move |i| (ring[i], ring[(i + 1) % ring.len()]))
+        })
+    };
+
+    let mut candidates: Vec<usize> = (0..polygon.len()).collect();
+    candidates.sort_by(|&a, &b| {
+        let a = (points[polygon[a] as usize] - from).len2();
+        let b = (points[polygon[b] as usize] - from).len2();
+        a.total_cmp(&b)
+    });
+
+    candidates
+        .into_iter()
+        .find(|&candidate| {
+            let to = points[polygon[candidate] as usize];
+            edges().all(|(a, b)| {
+                let (a, b) = (points[a as usize], points[b as usize]);
+                a == to || b == to || a == from || b == from || !crosses(from, to, a, b)
+            })
+        })
+        .unwrap_or(0)
+}
+
+/// Cut triangles off of a counter-clockwise polygon one corner at a time
+fn clip_ears(points: &[Vec2], mut polygon: Vec<u32>) -> Vec<u32> {
+    let point = |i: u32| points[i as usize];
+    let mut triangles = Vec::with_capacity(polygon.len().saturating_sub(2) * 3);
+
+    while polygon.len() > 3 {
+        let count = polygon.len();
+        let corner = |i: usize| {
+            (
+                polygon[(i + count - 1) % count],
+                polygon[i],
+                polygon[(i + 1) % count],
+            )
+        };
+
+        let ear = (0..count).find(|&i| {
+            let (a, b, c) = corner(i);
+            let (a, b, c) = (point(a), point(b), point(c));
+            if cross(b - a, c - b) <= 0.0 {
+                return false;
+            }
+            // points shared with the corner through a bridge do not count
+            polygon.iter().all(|&p| {
+                let p = point(p);
+                p == a || p == b || p == c || !in_triangle(p, a, b, c)
+            })
+        });
+
+        // polygons which are slightly off only lose a sliver
+        let i = ear.unwrap_or_else(|| {
+            (0..count)
+                .find(|&i| {
+                    let (a, b, c) = corner(i);
+                    cross(point(b) - point(a), point(c) - point(b)) >= 0.0
+                })
+                .unwrap_or(0)
+        });
+
+        let (a, b, c) = corner(i);
+        if cross(point(b) - point(a), point(c) - point(b)) > 0.0 {
+            triangles.extend(&[a, b, c]);
+        }
+        polygon.remove(i);
+    }
+
+    if polygon.len() == 3 {
+        triangles.extend(&polygon);
+    }
+    triangles
+}
+
+/// Z component of the cross product
+fn cross(a: Vec2, b: Vec2) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+/// Whether `p` is inside of or on the edge of a counter-clockwise triangle
+fn in_triangle(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    cross(b - a, p - a) >= 0.0 && cross(c - b, p - b) >= 0.0 && cross(a - c, p - c) >= 0.0
+}
+
+/// Whether segments `ab` and `cd` cross each other
+fn crosses(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+    let side = |p: Vec2, q: Vec2, r: Vec2| cross(q - p, r - p).signum();
+    side(a, b, c) != side(a, b, d) && side(c, d, a) != side(c, d, b)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{is_convex, signed_area, triangulate};
+    use crate::Vec2;
+
+    /// Total area covered by triangles
+    fn area(points: &[Vec2], triangles: &[u32]) -> f32 {
+        triangles
+            .chunks(3)
+            .map(|t| {
+                let triangle = [
+                    points[t[0] as usize],
+                    points[t[1] as usize],
+                    points[t[2] as usize],
+                ];
+                signed_area(&triangle).abs() * 0.5
+            })
+            .sum()
+    }
+
+    #[test]
+    fn concave_polygons_are_clipped() {
+        // an L with a reflex corner, wound clockwise on screen
+        let l = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 4.0),
+            Vec2::new(4.0, 4.0),
+            Vec2::new(4.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ];
+        assert!(!is_convex(&l));
+        let triangles = triangulate(&l, &[]);
+        assert_eq!(triangles.len(), (l.len() - 2) * 3);
+        assert!((area(&l, &triangles) - 64.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn holes_are_left_empty() {
+        let outline = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ];
+        let hole = [
+            Vec2::new(3.0, 3.0),
+            Vec2::new(7.0, 3.0),
+            Vec2::new(7.0, 7.0),
+            Vec2::new(3.0, 7.0),
+        ];
+        let mut points = outline.to_vec();
+        points.extend_from_slice(&hole);
+
+        let triangles = triangulate(&outline, &[&hole]);
+        assert!((area(&points, &triangles) - 84.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn non_finite_points_do_not_panic() {
+        let nan = Vec2::new(f32::NAN, 0.0);
+        let outline = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            nan,
+            Vec2::new(0.0, 10.0),
+        ];
+        let hole = [Vec2::new(3.0, 3.0), nan, Vec2::new(3.0, 7.0)];
+        triangulate(&outline, &[&hole, &hole]);
+    }
+}
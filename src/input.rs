@@ -0,0 +1,317 @@
+//! Input types consumed by the UI each frame
+
+use crate::math::Vec2;
+
+/// High level input consumed by the UI
+///
+/// Input is gathered by the backend over the course of a frame and handed to
+/// the UI through `UI::next_frame`.
+///
+/// ```
+/// use immediate_mode::{Input, Key, Modifiers, MouseButton, Vec2};
+///
+/// let mut input = Input::new(Some(Vec2::new(10.0, 10.0)), false);
+/// input.mouse_button(MouseButton::Right, true);
+/// input.scroll_lines(Vec2::new(0.0, -1.0));
+/// input.key_event(Key::C, true);
+/// input.text_event('c');
+/// input.set_modifiers(Modifiers {
+///     ctrl: true,
+///     ..Modifiers::default()
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct Input {
+    pub(crate) mouse_pos: Option<Vec2>,
+    pub(crate) mouse_down: [bool; MouseButton::COUNT],
+    pub(crate) scroll: Scroll,
+    pub(crate) delta_time: Option<f32>,
+    pub(crate) keys: Vec<KeyEvent>,
+    pub(crate) modifiers: Modifiers,
+    pub(crate) text: String,
+}
+
+impl Input {
+    /// Create input necessary to process the UI
+    pub fn new(mouse_pos: Option<Vec2>, mouse_down: bool) -> Self {
+        let mut buttons = [false; MouseButton::COUNT];
+        buttons[MouseButton::Left as usize] = mouse_down;
+
+        Input {
+            mouse_pos,
+            mouse_down: buttons,
+            scroll: Scroll::default(),
+            delta_time: None,
+            keys: Vec::new(),
+            modifiers: Modifiers::default(),
+            text: String::new(),
+        }
+    }
+
+    /// Set whether a mouse button is held down
+    ///
+    /// The `mouse_down` argument of `Input::new` sets the left button.
+    pub fn mouse_button(&mut self, button: MouseButton, down: bool) -> &mut Self {
+        self.mouse_down[button as usize] = down;
+        self
+    }
+
+    /// Accumulate scrolling measured in lines, as reported by most mouse
+    /// wheels
+    pub fn scroll_lines(&mut self, delta: Vec2) -> &mut Self {
+        self.scroll.lines = self.scroll.lines + delta;
+        self
+    }
+
+    /// Accumulate scrolling measured in pixels, as reported by touchpads
+    pub fn scroll_pixels(&mut self, delta: Vec2) -> &mut Self {
+        self.scroll.pixels = self.scroll.pixels + delta;
+        self
+    }
+
+    /// Set the time in seconds since the previous frame
+    ///
+    /// When not set, the UI measures the time between calls to
+    /// `UI::next_frame` itself.
+    pub fn set_delta_time(&mut self, seconds: f32) -> &mut Self {
+        self.delta_time = Some(seconds);
+        self
+    }
+
+    /// Is the mouse button held down?
+    pub fn mouse_down(&self, button: MouseButton) -> bool {
+        self.mouse_down[button as usize]
+    }
+
+    /// Record a key being pressed or released during this frame
+    ///
+    /// Events should be recorded in the order they were received.
+    pub fn key_event(&mut self, key: Key, pressed: bool) -> &mut Self {
+        self.keys.push(KeyEvent { key, pressed });
+        self
+    }
+
+    /// Record a character of text committed during this frame
+    ///
+    /// Control characters such as backspace or escape are ignored; report
+    /// them as key events instead.
+    pub fn text_event(&mut self, c: char) -> &mut Self {
+        if !c.is_control() {
+            self.text.push(c);
+        }
+        self
+    }
+
+    /// Set the modifier keys held at the end of this frame
+    pub fn set_modifiers(&mut self, modifiers: Modifiers) -> &mut Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    /// Key events in the order they were recorded
+    pub fn keys(&self) -> &[KeyEvent] {
+        self.keys.as_slice()
+    }
+}
+
+/// Buttons on a mouse
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    /// Primary button
+    Left = 0,
+    /// Secondary button, usually opens context menus
+    Right = 1,
+    /// Wheel button
+    Middle = 2,
+    /// First extra button, often "back"
+    Extra1 = 3,
+    /// Second extra button, often "forward"
+    Extra2 = 4,
+}
+
+impl MouseButton {
+    /// Number of buttons tracked by the UI
+    pub(crate) const COUNT: usize = 5;
+}
+
+/// Scrolling accumulated over a frame
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Scroll {
+    /// Scroll distance in lines
+    pub lines: Vec2,
+    /// Scroll distance in pixels
+    pub pixels: Vec2,
+}
+
+impl Scroll {
+    /// There was no scrolling
+    pub fn is_zero(&self) -> bool {
+        self.lines == Vec2::zero() && self.pixels == Vec2::zero()
+    }
+}
+
+/// A key was pressed or released
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct KeyEvent {
+    /// The logical key which changed state
+    pub key: Key,
+    /// Whether the key went down (`true`) or up (`false`)
+    pub pressed: bool,
+}
+
+/// State of the modifier keys
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    /// Either shift key
+    pub shift: bool,
+    /// Either control key
+    pub ctrl: bool,
+    /// Either alt or option key
+    pub alt: bool,
+    /// The super, windows or command key
+    pub logo: bool,
+}
+
+/// Logical keys the UI understands
+///
+/// Backends map their own key codes onto these; keys the UI has no use for
+/// can simply be dropped.  Letters, digits and function keys are meant for
+/// shortcuts, while typed text arrives through `Input::text_event`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Key {
+    // Navigation
+    /// Move focus to the next element
+    Tab,
+    /// Left arrow
+    Left,
+    /// Right arrow
+    Right,
+    /// Up arrow
+    Up,
+    /// Down arrow
+    Down,
+    /// Page up
+    PageUp,
+    /// Page down
+    PageDown,
+    /// Home
+    Home,
+    /// End
+    End,
+
+    // Editing
+    /// Insert
+    Insert,
+    /// Delete forward
+    Delete,
+    /// Delete backward
+    Backspace,
+    /// Space bar
+    Space,
+    /// Enter or return
+    Enter,
+    /// Escape
+    Escape,
+
+    // Letters
+    /// The A key
+    A,
+    /// The B key
+    B,
+    /// The C key
+    C,
+    /// The D key
+    D,
+    /// The E key
+    E,
+    /// The F key
+    F,
+    /// The G key
+    G,
+    /// The H key
+    H,
+    /// The I key
+    I,
+    /// The J key
+    J,
+    /// The K key
+    K,
+    /// The L key
+    L,
+    /// The M key
+    M,
+    /// The N key
+    N,
+    /// The O key
+    O,
+    /// The P key
+    P,
+    /// The Q key
+    Q,
+    /// The R key
+    R,
+    /// The S key
+    S,
+    /// The T key
+    T,
+    /// The U key
+    U,
+    /// The V key
+    V,
+    /// The W key
+    W,
+    /// The X key
+    X,
+    /// The Y key
+    Y,
+    /// The Z key
+    Z,
+
+    // Digits along the top of the keyboard
+    /// The 0 key
+    Num0,
+    /// The 1 key
+    Num1,
+    /// The 2 key
+    Num2,
+    /// The 3 key
+    Num3,
+    /// The 4 key
+    Num4,
+    /// The 5 key
+    Num5,
+    /// The 6 key
+    Num6,
+    /// The 7 key
+    Num7,
+    /// The 8 key
+    Num8,
+    /// The 9 key
+    Num9,
+
+    // Function keys
+    /// The F1 key
+    F1,
+    /// The F2 key
+    F2,
+    /// The F3 key
+    F3,
+    /// The F4 key
+    F4,
+    /// The F5 key
+    F5,
+    /// The F6 key
+    F6,
+    /// The F7 key
+    F7,
+    /// The F8 key
+    F8,
+    /// The F9 key
+    F9,
+    /// The F10 key
+    F10,
+    /// The F11 key
+    F11,
+    /// The F12 key
+    F12,
+}
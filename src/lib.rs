@@ -10,30 +10,19 @@ pub mod text;
 // modules for code organization:
 
 mod color;
+mod input;
 mod math;
+mod state;
 
 pub use crate::color::{theme, Color, Theme};
-pub use crate::math::Vec2;
+pub use crate::input::{Input, Key, KeyEvent, Modifiers, MouseButton, Scroll};
+pub use crate::math::{Bezier, Flatten, Rect, Vec2};
 
+use std::any::Any;
 use std::collections::hash_map::RandomState;
+use std::collections::HashSet;
 use std::fmt::{self, Debug, Formatter};
-
-/// High level input consumed by the UI
-#[derive(Debug, Clone)]
-pub struct Input {
-    mouse_pos: Option<Vec2>,
-    mouse_down: bool,
-}
-
-impl Input {
-    /// Create input necessary to process the UI
-    pub fn new(mouse_pos: Option<Vec2>, mouse_down: bool) -> Self {
-        Input {
-            mouse_pos,
-            mouse_down,
-        }
-    }
-}
+use std::time::Instant;
 
 /// Interface used to gather commands which draw a single frame of the UI
 ///
@@ -46,6 +35,9 @@ where
     draw_data: draw::DrawData<V>,
     context: Context,
     id_stack: Vec<u64>,
+    clip_stack: Vec<Rect>,
+    fonts: Option<text::Fonts>,
+    theme: Theme,
     input: Input,
 }
 
@@ -57,19 +49,26 @@ where
     pub fn new(input: Input) -> Self {
         use crate::draw::DrawData;
 
+        let mut context = Context::default();
+        context.process_input(&input);
+
         UI {
             id_stack: Vec::with_capacity(8),
-            input: input,
-            context: Context::default(),
+            clip_stack: Vec::with_capacity(8),
+            fonts: None,
+            theme: Theme::DARK,
+            input,
+            context,
             draw_data: DrawData::<V>::default(),
         }
     }
 
     /// Process UI for the next frame
     pub fn next_frame(&mut self, input: Input) {
+        self.context.process_input(&input);
         self.input = input;
-        self.draw_data.indicies.clear();
-        self.draw_data.verts.clear();
+        self.clip_stack.clear();
+        self.draw_data.clear();
     }
 
     /// Draw primitives directly to the draw data
@@ -80,6 +79,83 @@ where
         command(&mut self.draw_data)
     }
 
+    /// Set the fonts used to draw text
+    ///
+    /// The atlas texture should be bound as `TextureId::DEFAULT`.
+    pub fn set_fonts(&mut self, fonts: text::Fonts) {
+        self.fonts = Some(fonts);
+    }
+
+    /// Access the fonts used to draw text, for example to upload their
+    /// texture
+    pub fn fonts(&self) -> Option<&text::Fonts> {
+        self.fonts.as_ref()
+    }
+
+    /// Mutably access the fonts used to draw text
+    pub fn fonts_mut(&mut self) -> Option<&mut text::Fonts> {
+        self.fonts.as_mut()
+    }
+
+    /// Set the colors and shadows used by elements of the UI
+    ///
+    /// The UI starts with `Theme::DARK`.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    /// Colors and shadows used by elements of the UI
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Draw text with a family of the UI's fonts
+    ///
+    /// Does nothing when no fonts have been set.
+    pub fn text(
+        &mut self,
+        family: text::FontFamily,
+        size: f32,
+        pos: Vec2,
+        color: Color,
+        text: &str,
+    ) {
+        if let Some(fonts) = self.fonts.as_mut() {
+            self.draw_data.text(fonts, family, size, pos, color, text);
+        }
+    }
+
+    /// Lay out and draw a block of text within a rectangle
+    ///
+    /// Returns the layout so characters can be hit-tested; it is empty when
+    /// no fonts have been set.
+    pub fn text_block<R: Into<Rect>>(
+        &mut self,
+        family: text::FontFamily,
+        options: &text::TextOptions,
+        bounds: R,
+        color: Color,
+        text: &str,
+    ) -> text::TextBlock {
+        let fonts = match self.fonts.as_mut() {
+            Some(fonts) => fonts,
+            None => return text::TextBlock::default(),
+        };
+
+        let block = text::TextBlock::new(fonts, family, options, bounds.into(), text);
+        self.draw_data.text_block(fonts, color, &block);
+        block
+    }
+
+    /// Size of text drawn with a family of the UI's fonts
+    ///
+    /// Text has no size when no fonts have been set.
+    pub fn measure_text(&self, family: text::FontFamily, size: f32, text: &str) -> Vec2 {
+        self.fonts
+            .as_ref()
+            .map_or(Vec2::zero(), |fonts| fonts.measure_text(family, size, text))
+    }
+
     /// Derive an ID to keep track of an element between frames
     pub fn calculate_id<H: std::hash::Hash>(&self, into_id: H) -> ID {
         use std::hash::{BuildHasher, Hash, Hasher};
@@ -99,12 +175,66 @@ where
         self
     }
 
+    /// Clip drawing and mouse interaction to a rectangle until the matching
+    /// `pop_clip_rect`
+    ///
+    /// Clip rectangles nest; the new clip rectangle is limited to the area of
+    /// the current one.
+    pub fn push_clip_rect<R: Into<Rect>>(&mut self, rect: R) {
+        let rect = rect.into();
+        let clip = match self.clip_stack.last() {
+            // rectangles which do not overlap clip everything
+            Some(current) => current
+                .intersect(rect)
+                .unwrap_or(Rect::new(rect.min, rect.min)),
+            None => rect,
+        };
+        self.clip_stack.push(clip);
+        self.draw_data.set_clip_rect(Some(clip));
+    }
+
+    /// Restore the clip rectangle from before the last `push_clip_rect`
+    pub fn pop_clip_rect(&mut self) {
+        self.clip_stack.pop();
+        self.draw_data
+            .set_clip_rect(self.clip_stack.last().cloned());
+    }
+
     /// Complete this frame of the UI and render
     pub fn finish_frame<'a>(&'a mut self) -> Renderer<'a, V> {
+        if self.key_pressed(Key::Tab) {
+            self.context.cycle_focus(self.input.modifiers.shift);
+        }
         self.context.finish_frame();
         Renderer { ui: self }
     }
 
+    /// Access state of type `T` stored for an element across frames
+    ///
+    /// State is created with `Default` on first access.  State which is not
+    /// accessed during a frame is dropped when the frame finishes, and a clone
+    /// of the UI starts without any state.
+    ///
+    /// ```
+    /// use immediate_mode::{draw::Vert, Input, UI};
+    ///
+    /// let mut ui = UI::<Vert>::new(Input::new(None, false));
+    /// let id = ui.calculate_id("counter");
+    /// *ui.state::<u32>(id) += 1;
+    /// ui.finish_frame();
+    ///
+    /// ui.next_frame(Input::new(None, false));
+    /// assert_eq!(*ui.state::<u32>(id), 1);
+    /// ```
+    pub fn state<T: Any + Default>(&mut self, id: ID) -> &mut T {
+        self.context.state.get::<T>(id)
+    }
+
+    /// Drop state of type `T` stored for an element
+    pub fn remove_state<T: Any>(&mut self, id: ID) {
+        self.context.state.remove::<T>(id);
+    }
+
     /// Was this ID previously declared active?
     pub fn is_held(&self, id: ID) -> bool {
         id == self.context.held_id
@@ -115,33 +245,243 @@ where
         id == self.context.prev_hover_id
     }
 
-    fn hit_test(pos: Vec2, region: (Vec2, Vec2)) -> bool {
-        region.0.x < pos.x && pos.x < region.1.x && region.0.y < pos.y && pos.y < region.1.y
+    /// Register an element which can receive keyboard focus this frame
+    ///
+    /// Tab and Shift-Tab cycle focus through focusable elements in the order
+    /// they were registered.  Register an element before calling `event` on
+    /// it so that pressing the mouse over it also gives it focus.
+    ///
+    /// ```
+    /// use immediate_mode::{draw::Vert, Input, Key, UI};
+    ///
+    /// let mut ui = UI::<Vert>::new(Input::new(None, false));
+    /// let mut input = Input::new(None, false);
+    /// input.key_event(Key::Tab, true);
+    /// ui.next_frame(input);
+    ///
+    /// ui.set_focusable(1);
+    /// ui.set_focusable(2);
+    /// ui.finish_frame();
+    /// assert!(ui.has_focus(1));
+    /// ```
+    pub fn set_focusable(&mut self, id: ID) {
+        self.context.focusable.push(id);
+    }
+
+    /// Give an element keyboard focus
+    pub fn request_focus(&mut self, id: ID) {
+        self.context.focus_id = id;
+        self.context.focus_requested = true;
+    }
+
+    /// Does this ID have keyboard focus?
+    pub fn has_focus(&self, id: ID) -> bool {
+        id != 0 && id == self.context.focus_id
+    }
+
+    /// Give up keyboard focus if this ID has it
+    pub fn surrender_focus(&mut self, id: ID) {
+        if self.has_focus(id) {
+            self.context.focus_id = 0;
+        }
+    }
+
+    /// Was this key pressed during the current frame?
+    pub fn key_pressed(&self, key: Key) -> bool {
+        self.input.keys.iter().any(|e| e.key == key && e.pressed)
+    }
+
+    /// Was this key released during the current frame?
+    pub fn key_released(&self, key: Key) -> bool {
+        self.input.keys.iter().any(|e| e.key == key && !e.pressed)
+    }
+
+    /// Is this key currently held down?
+    ///
+    /// Keys stay down across frames until a release event is received
+    /// ```
+    /// use immediate_mode::{draw::Vert, Input, Key, UI};
+    ///
+    /// let mut input = Input::new(None, false);
+    /// input.key_event(Key::Enter, true);
+    /// let mut ui = UI::<Vert>::new(input);
+    /// assert!(ui.key_pressed(Key::Enter));
+    ///
+    /// ui.next_frame(Input::new(None, false));
+    /// assert!(!ui.key_pressed(Key::Enter));
+    /// assert!(ui.key_down(Key::Enter));
+    /// ```
+    pub fn key_down(&self, key: Key) -> bool {
+        self.context.keys_down.contains(&key)
+    }
+
+    /// Modifier keys held during the current frame
+    pub fn modifiers(&self) -> Modifiers {
+        self.input.modifiers
+    }
+
+    /// Text typed during the current frame
+    pub fn text_input(&self) -> &str {
+        &self.input.text
+    }
+
+    /// Is the mouse button currently held down?
+    pub fn mouse_down(&self, button: MouseButton) -> bool {
+        self.input.mouse_down(button)
+    }
+
+    /// Scrolling during the current frame
+    pub fn scroll(&self) -> Scroll {
+        self.input.scroll
+    }
+
+    /// Seconds elapsed since the UI was created
+    pub fn time(&self) -> f64 {
+        self.context.time
+    }
+
+    /// Configure how close in time (seconds) and space (pixels) two clicks
+    /// must be to count as a double click
+    ///
+    /// ```
+    /// use immediate_mode::{draw::Vert, Input, Vec2, UI};
+    ///
+    /// let region = (Vec2::new(0.0, 0.0), Vec2::new(10.0, 10.0));
+    /// let input = |down| {
+    ///     let mut input = Input::new(Some(Vec2::new(5.0, 5.0)), down);
+    ///     input.set_delta_time(0.05);
+    ///     input
+    /// };
+    ///
+    /// let mut ui = UI::<Vert>::new(input(false));
+    /// ui.set_double_click_threshold(0.3, 6.0);
+    ///
+    /// // press and release twice in quick succession
+    /// let mut double_clicked = false;
+    /// for &down in &[true, false, true, false] {
+    ///     ui.next_frame(input(down));
+    ///     double_clicked = ui.event(1, region).is_double_clicked;
+    /// }
+    /// assert!(double_clicked);
+    /// ```
+    pub fn set_double_click_threshold(&mut self, time: f32, distance: f32) {
+        self.context.double_click_time = time;
+        self.context.double_click_distance = distance;
+    }
+
+    /// Configure how far in pixels the mouse must move while held before
+    /// the press is considered a drag rather than a click
+    ///
+    /// ```
+    /// use immediate_mode::{draw::Vert, Input, Vec2, UI};
+    ///
+    /// let region = (Vec2::new(0.0, 0.0), Vec2::new(10.0, 10.0));
+    /// let mut ui = UI::<Vert>::new(Input::new(Some(Vec2::new(5.0, 5.0)), false));
+    /// ui.set_drag_threshold(3.0);
+    ///
+    /// // press inside the region then drag outside of it
+    /// ui.next_frame(Input::new(Some(Vec2::new(5.0, 5.0)), true));
+    /// ui.event(1, region);
+    /// ui.next_frame(Input::new(Some(Vec2::new(20.0, 5.0)), true));
+    /// let event = ui.event(1, region);
+    ///
+    /// assert!(event.is_dragged);
+    /// assert_eq!(event.drag_start, Some(Vec2::new(5.0, 5.0)));
+    /// assert_eq!(event.drag_delta, Vec2::new(15.0, 0.0));
+    /// assert_eq!(event.drag_total, Vec2::new(15.0, 0.0));
+    /// ```
+    pub fn set_drag_threshold(&mut self, distance: f32) {
+        self.context.drag_threshold = distance;
     }
 
     /// Check a region associated with an ID for mouse interaction
-    pub fn event(&mut self, id: ID, region: (Vec2, Vec2)) -> Event {
+    ///
+    /// An element pressed by the mouse stays held, and keeps reporting drag
+    /// movement, until the button is released even if the mouse leaves the
+    /// region.
+    pub fn event<R: Into<Rect>>(&mut self, id: ID, region: R) -> Event {
+        let region = region.into();
+
         // Click when button was held but is no longer held
         let was_held = id == self.context.held_id;
+        let was_right_held = id == self.context.right_held_id;
         let hit = if let Some(p) = self.input.mouse_pos {
-            Self::hit_test(p, region)
+            // only the visible part of the region can be hit
+            let visible = match self.clip_stack.last() {
+                Some(clip) => clip.contains(p),
+                None => true,
+            };
+            visible && region.contains(p)
         } else {
             false
         };
 
-        // update the active and hovered elements based on the hit results
+        let left_down = self.input.mouse_down(MouseButton::Left);
+        let right_down = self.input.mouse_down(MouseButton::Right);
+
+        // update the active and hovered elements based on the hit results;
+        // elements are only held when the button goes down over them
         if hit {
-            self.context.held_id = if self.input.mouse_down { id } else { 0 };
+            if self.context.mouse_pressed(MouseButton::Left) {
+                self.context.held_id = id;
+                if self.context.focusable.contains(&id) {
+                    self.context.focus_id = id;
+                }
+            }
+            if self.context.mouse_pressed(MouseButton::Right) {
+                self.context.right_held_id = id;
+            }
             self.context.hover_id = id;
-        } else if was_held {
+        }
+        if was_held && !left_down {
             self.context.held_id = 0;
         }
+        if was_right_held && !right_down {
+            self.context.right_held_id = 0;
+        }
+
+        let is_held = left_down && was_held;
+        let is_clicked = !left_down && was_held && hit && !self.context.dragging;
+        let is_double_clicked = is_clicked && self.context.double_click(id, self.input.mouse_pos);
+
+        // the focused element is also clicked by pressing enter or space
+        let is_focused = self.has_focus(id);
+        let is_activated =
+            is_focused && (self.key_pressed(Key::Enter) || self.key_pressed(Key::Space));
+
+        // drag from where the mouse was pressed, even outside the region
+        let press = self.context.press_origin.filter(|_| is_held);
+        let drag_total = match (press, self.input.mouse_pos) {
+            (Some((origin, _)), Some(pos)) => pos - origin,
+            _ => Vec2::zero(),
+        };
 
         Event {
-            is_clicked: !self.input.mouse_down && was_held && hit,
+            is_clicked: is_clicked || is_activated,
+            is_right_clicked: !right_down && was_right_held && hit,
+            is_double_clicked,
             is_hovered: self.context.prev_hover_id == id,
-            is_held: self.input.mouse_down && was_held,
+            is_held,
+            is_dragged: is_held && self.context.dragging,
+            is_focused,
             mouse_pos: self.input.mouse_pos.filter(|_| hit),
+            drag_start: press.map(|(origin, _)| origin),
+            drag_delta: if is_held {
+                self.context.mouse_delta
+            } else {
+                Vec2::zero()
+            },
+            drag_total,
+            scroll: if hit {
+                self.input.scroll
+            } else {
+                Scroll::default()
+            },
+            click_pos: if is_activated && !is_clicked {
+                Some(region.center())
+            } else {
+                self.input.mouse_pos.filter(|_| is_clicked)
+            },
         }
     }
 
@@ -163,8 +503,41 @@ pub type ID = u64;
 #[derive(Clone)]
 pub(crate) struct Context {
     held_id: ID,
+    right_held_id: ID,
     hover_id: ID,
     prev_hover_id: ID,
+    focus_id: ID,
+    /// Focus was requested this frame and should be kept even if the element
+    /// has not registered as focusable yet
+    focus_requested: bool,
+    /// Focusable elements in the order they were registered this frame
+    focusable: Vec<ID>,
+    keys_down: HashSet<Key>,
+    /// Widget state which persists between frames
+    state: state::StateStore,
+    /// Mouse buttons held during the previous frame
+    mouse_was_down: [bool; MouseButton::COUNT],
+    /// Mouse buttons which went down this frame
+    mouse_pressed: [bool; MouseButton::COUNT],
+    prev_mouse_pos: Option<Vec2>,
+    /// Mouse movement since the previous frame
+    mouse_delta: Vec2,
+    /// Position and time the left mouse button was last pressed
+    press_origin: Option<(Vec2, f64)>,
+    /// The mouse moved past the drag threshold since it was pressed
+    dragging: bool,
+    /// Distance the mouse must move while held to start a drag
+    drag_threshold: f32,
+    /// Seconds since the UI was created
+    time: f64,
+    /// Start of the current frame, used when input has no delta time
+    frame_start: Instant,
+    /// Element, time and position of the last click
+    last_click: Option<(ID, f64, Vec2)>,
+    /// Maximum seconds between clicks which form a double click
+    double_click_time: f32,
+    /// Maximum distance between clicks which form a double click
+    double_click_distance: f32,
     id_hasher: RandomState,
 }
 
@@ -172,8 +545,26 @@ impl Default for Context {
     fn default() -> Self {
         Context {
             held_id: 0,
+            right_held_id: 0,
             hover_id: 0,
             prev_hover_id: 0,
+            focus_id: 0,
+            focus_requested: false,
+            focusable: Vec::new(),
+            keys_down: HashSet::new(),
+            state: state::StateStore::default(),
+            mouse_was_down: [false; MouseButton::COUNT],
+            mouse_pressed: [false; MouseButton::COUNT],
+            prev_mouse_pos: None,
+            mouse_delta: Vec2::zero(),
+            press_origin: None,
+            dragging: false,
+            drag_threshold: 3.0,
+            time: 0.0,
+            frame_start: Instant::now(),
+            last_click: None,
+            double_click_time: 0.3,
+            double_click_distance: 6.0,
             id_hasher: RandomState::new(),
         }
     }
@@ -189,23 +580,144 @@ impl Debug for Context {
 }
 
 impl Context {
+    /// Advance the frame timer and track which keys are held based on this
+    /// frame's key events
+    fn process_input(&mut self, input: &Input) {
+        let now = Instant::now();
+        let delta_time = match input.delta_time {
+            Some(dt) => f64::from(dt),
+            None => now.duration_since(self.frame_start).as_secs_f64(),
+        };
+        self.frame_start = now;
+        self.time += delta_time;
+
+        for (i, pressed) in self.mouse_pressed.iter_mut().enumerate() {
+            *pressed = input.mouse_down[i] && !self.mouse_was_down[i];
+        }
+        self.mouse_was_down = input.mouse_down;
+
+        self.mouse_delta = match (input.mouse_pos, self.prev_mouse_pos) {
+            (Some(pos), Some(prev)) => pos - prev,
+            _ => Vec2::zero(),
+        };
+        self.prev_mouse_pos = input.mouse_pos;
+
+        if self.mouse_pressed(MouseButton::Left) {
+            self.press_origin = input.mouse_pos.map(|pos| (pos, self.time));
+            self.dragging = false;
+        }
+        if input.mouse_down(MouseButton::Left) {
+            if let (Some(pos), Some((origin, _))) = (input.mouse_pos, self.press_origin) {
+                self.dragging |= (pos - origin).len() > self.drag_threshold;
+            }
+        }
+
+        for event in input.keys.iter() {
+            if event.pressed {
+                self.keys_down.insert(event.key);
+            } else {
+                self.keys_down.remove(&event.key);
+            }
+        }
+    }
+
+    /// Did the mouse button go down this frame?
+    fn mouse_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_pressed[button as usize]
+    }
+
+    /// Record a click on an element, returning whether it completes a double
+    /// click
+    fn double_click(&mut self, id: ID, pos: Option<Vec2>) -> bool {
+        let pos = pos.unwrap_or_default();
+        let is_double = match self.last_click {
+            Some((last_id, time, last_pos)) => {
+                last_id == id
+                    && self.time - time <= f64::from(self.double_click_time)
+                    && (pos - last_pos).len() <= self.double_click_distance
+            }
+            None => false,
+        };
+
+        // a double click consumes the first click so a third click starts over
+        self.last_click = if is_double {
+            None
+        } else {
+            Some((id, self.time, pos))
+        };
+        is_double
+    }
+
+    /// Move focus to the next (or previous) focusable element
+    fn cycle_focus(&mut self, backwards: bool) {
+        let count = self.focusable.len();
+        if count == 0 {
+            return;
+        }
+
+        let current = self.focusable.iter().position(|&id| id == self.focus_id);
+        let next = match (current, backwards) {
+            (Some(i), false) => (i + 1) % count,
+            (Some(i), true) => (i + count - 1) % count,
+            (None, false) => 0,
+            (None, true) => count - 1,
+        };
+        self.focus_id = self.focusable[next];
+        self.focus_requested = true;
+    }
+
     fn finish_frame(&mut self) {
         self.prev_hover_id = self.hover_id;
         self.hover_id = 0;
+
+        // drop focus from elements which have gone away
+        if !self.focus_requested && !self.focusable.contains(&self.focus_id) {
+            self.focus_id = 0;
+        }
+        self.focus_requested = false;
+        self.focusable.clear();
+
+        self.state.collect_garbage();
     }
 }
 
+/// Pixel size of text in tooltips
+const TOOLTIP_TEXT_SIZE: f32 = 16.0;
+
+/// Space between the edge of a tooltip and its text
+const TOOLTIP_PADDING: f32 = 4.0;
+
 /// Result of a user interaction with a specific region of the UI
 #[derive(Debug)]
 pub struct Event {
-    /// The mouse went up over this region
+    /// The mouse went up over this region, or the focused element was
+    /// activated with enter or space
     pub is_clicked: bool,
+    /// The right mouse button went up over this region
+    pub is_right_clicked: bool,
+    /// This click closely followed another click on this region
+    pub is_double_clicked: bool,
     /// The element is hovered
     pub is_hovered: bool,
     /// The element has the mouse button held down
     pub is_held: bool,
+    /// The element is held and the mouse moved past the drag threshold
+    pub is_dragged: bool,
+    /// The element has keyboard focus
+    pub is_focused: bool,
     /// The position of the mouse
     pub mouse_pos: Option<Vec2>,
+    /// Where the mouse was pressed while the element is held
+    pub drag_start: Option<Vec2>,
+    /// Mouse movement since the last frame while the element is held
+    pub drag_delta: Vec2,
+    /// Mouse movement since the press while the element is held
+    pub drag_total: Vec2,
+    /// Scrolling over this region during the frame
+    pub scroll: Scroll,
+    /// Where the click happened; the center of the region when activated by
+    /// keyboard
+    pub click_pos: Option<Vec2>,
 }
 
 impl Event {
@@ -222,7 +734,22 @@ impl Event {
     /// Perform an action when the UI detects a click
     #[inline]
     pub fn on_click<F: FnOnce(Vec2)>(&self, action: F) -> &Self {
-        self.when(self.is_clicked, action)
+        if let Some(pos) = self.click_pos.filter(|_| self.is_clicked) {
+            action(pos);
+        }
+        self
+    }
+
+    /// Perform an action when the UI detects a right click
+    #[inline]
+    pub fn on_right_click<F: FnOnce(Vec2)>(&self, action: F) -> &Self {
+        self.when(self.is_right_clicked, action)
+    }
+
+    /// Perform an action when the UI detects a double click
+    #[inline]
+    pub fn on_double_click<F: FnOnce(Vec2)>(&self, action: F) -> &Self {
+        self.when(self.is_double_clicked, action)
     }
 
     /// Perform an action when hovering over the UI
@@ -237,21 +764,60 @@ impl Event {
         self.when(self.is_held, action)
     }
 
+    /// Perform an action with the mouse movement since the last frame while
+    /// this element is dragged
+    #[inline]
+    pub fn on_drag<F: FnOnce(Vec2)>(&self, action: F) -> &Self {
+        if self.is_dragged {
+            action(self.drag_delta);
+        }
+        self
+    }
+
     /// Pop up some text on hover
+    ///
+    /// The text uses the proportional family and the colors of the UI's theme.
+    /// Without fonts only the background is drawn, sized roughly for the text.
     #[inline]
     pub fn tooltip<V, S: AsRef<str>>(&self, ui: &mut UI<V>, text: S) -> &Self
     where
         V: From<draw::Vert> + Copy,
     {
-        if let Some(pos) = self.mouse_pos.filter(|_| self.is_hovered && !self.is_held) {
-            let len = text.as_ref().len() as f32;
-            ui.draw(|d| {
-                d.rect(
-                    Theme::DARK.bg_child,
-                    pos,
-                    pos + Vec2::new(len * 20.0, -15.0),
-                )
-            });
+        let pos = match self.mouse_pos.filter(|_| self.is_hovered && !self.is_held) {
+            Some(pos) => pos,
+            None => return self,
+        };
+        let text = text.as_ref();
+        let family = text::FontFamily::PROPORTIONAL;
+        let size = match ui.fonts.as_mut() {
+            Some(fonts) => fonts.measure_text(family, TOOLTIP_TEXT_SIZE, text),
+            None => Vec2::new(
+                text.chars().count() as f32 * TOOLTIP_TEXT_SIZE * 0.5,
+                TOOLTIP_TEXT_SIZE,
+            ),
+        };
+
+        // place the tooltip above and to the right of the mouse
+        let size = size + TOOLTIP_PADDING * 2.0;
+        let region = Rect::from_origin_size(pos - Vec2::new(0.0, size.y), size);
+        let theme = &ui.theme;
+        ui.draw_data.shadow(
+            region,
+            0.0,
+            theme.shadow_size,
+            theme.shadow_offset,
+            theme.shadow,
+        );
+        ui.draw_data.rect(theme.bg_child, region);
+        if let Some(fonts) = ui.fonts.as_mut() {
+            ui.draw_data.text(
+                fonts,
+                family,
+                TOOLTIP_TEXT_SIZE,
+                region.min + TOOLTIP_PADDING,
+                theme.fg,
+                text,
+            );
         }
         self
     }
@@ -281,4 +847,9 @@ where
     pub fn indicies(&self) -> &[u32] {
         self.ui.draw_data.indicies()
     }
+
+    /// Access the batches of indicies to draw with their render state
+    pub fn commands(&self) -> impl Iterator<Item = draw::DrawCommand> + '_ {
+        self.ui.draw_data.commands()
+    }
 }
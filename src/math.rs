@@ -85,6 +85,24 @@ impl Vec2 {
     pub(crate) fn unit(self) -> Self {
         self * (1.0 / self.len().max(0.000_000_01))
     }
+
+    /// Component-wise minimum of two vectors
+    #[inline(always)]
+    pub(crate) fn min(self, rhs: Self) -> Self {
+        Vec2 {
+            x: self.x.min(rhs.x),
+            y: self.y.min(rhs.y),
+        }
+    }
+
+    /// Component-wise maximum of two vectors
+    #[inline(always)]
+    pub(crate) fn max(self, rhs: Self) -> Self {
+        Vec2 {
+            x: self.x.max(rhs.x),
+            y: self.y.max(rhs.y),
+        }
+    }
 }
 
 impl Default for Vec2 {
@@ -153,9 +171,9 @@ impl Mul<f32> for Vec2 {
 
 // Conversion to and from primitive types
 
-impl Into<(f32, f32)> for Vec2 {
-    fn into(self) -> (f32, f32) {
-        (self.x, self.y)
+impl From<Vec2> for (f32, f32) {
+    fn from(v: Vec2) -> (f32, f32) {
+        (v.x, v.y)
     }
 }
 
@@ -165,9 +183,9 @@ impl From<(f32, f32)> for Vec2 {
     }
 }
 
-impl Into<[f32; 2]> for Vec2 {
-    fn into(self) -> [f32; 2] {
-        [self.x, self.y]
+impl From<Vec2> for [f32; 2] {
+    fn from(v: Vec2) -> [f32; 2] {
+        [v.x, v.y]
     }
 }
 
@@ -177,6 +195,301 @@ impl From<[f32; 2]> for Vec2 {
     }
 }
 
+/// Most segments used to approximate a single arc
+const MAX_ARC_SEGMENTS: usize = 512;
+
+/// Number of straight segments needed to approximate an arc
+///
+/// Each segment strays at most `tolerance` from an arc of `radius` spanning
+/// `angle` radians, so large arcs get more segments than small ones.
+pub(crate) fn arc_segments(radius: f32, angle: f32, tolerance: f32) -> usize {
+    let angle = angle.abs();
+    if radius <= tolerance {
+        // the whole arc is within the tolerance
+        let quarter = std::f32::consts::FRAC_PI_2;
+        return ((angle / quarter).ceil() as usize).clamp(1, MAX_ARC_SEGMENTS);
+    }
+
+    // a chord spanning `step` radians strays `r * (1 - cos(step / 2))` from
+    // the arc at its middle
+    let step = 2.0 * (1.0 - tolerance / radius).acos();
+    ((angle / step).ceil() as usize).clamp(1, MAX_ARC_SEGMENTS)
+}
+
+/// Most segments used to approximate a single curve
+const MAX_CURVE_SEGMENTS: usize = 512;
+
+/// A quadratic or cubic Bézier curve
+///
+/// Curves are drawn and hit-tested as the straight segments between the
+/// points from `flatten`.
+///
+/// ```
+/// use immediate_mode::{Bezier, Vec2};
+///
+/// let wire = Bezier::Cubic {
+///     from: Vec2::new(0.0, 0.0),
+///     ctrl1: Vec2::new(50.0, 0.0),
+///     ctrl2: Vec2::new(50.0, 100.0),
+///     to: Vec2::new(100.0, 100.0),
+/// };
+///
+/// // hover the wire when the cursor is near one of its points
+/// let cursor = Vec2::new(50.0, 51.0);
+/// let near = |p: Vec2| (p.x - cursor.x).hypot(p.y - cursor.y) < 4.0;
+/// assert!(wire.flatten(0.25).any(near));
+/// ```
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Bezier {
+    /// Curve bending toward one control point
+    Quadratic {
+        /// Start of the curve
+        from: Vec2,
+        /// Control point
+        ctrl: Vec2,
+        /// End of the curve
+        to: Vec2,
+    },
+    /// Curve bending toward two control points
+    Cubic {
+        /// Start of the curve
+        from: Vec2,
+        /// Control point near the start
+        ctrl1: Vec2,
+        /// Control point near the end
+        ctrl2: Vec2,
+        /// End of the curve
+        to: Vec2,
+    },
+}
+
+impl Bezier {
+    /// Point on the curve at `t` between 0 at the start and 1 at the end
+    pub fn point(&self, t: f32) -> Vec2 {
+        let s = 1.0 - t;
+        match *self {
+            Bezier::Quadratic { from, ctrl, to } => {
+                from * (s * s) + ctrl * (2.0 * s * t) + to * (t * t)
+            }
+            Bezier::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => {
+                from * (s * s * s)
+                    + ctrl1 * (3.0 * s * s * t)
+                    + ctrl2 * (3.0 * s * t * t)
+                    + to * (t * t * t)
+            }
+        }
+    }
+
+    /// Points along the curve, from its start to its end, whose segments
+    /// stray at most `tolerance` from it
+    ///
+    /// Sharper bends get more points than gentle ones, and straight curves
+    /// are a single segment.
+    pub fn flatten(&self, tolerance: f32) -> Flatten {
+        // the curve strays from its chords by at most the largest second
+        // difference of its control points, scaled by its degree and divided
+        // by the square of the number of segments
+        let (scale, bend) = match *self {
+            Bezier::Quadratic { from, ctrl, to } => (0.25, (from - ctrl * 2.0 + to).len()),
+            Bezier::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => {
+                let a = (from - ctrl1 * 2.0 + ctrl2).len();
+                let b = (ctrl1 - ctrl2 * 2.0 + to).len();
+                (0.75, a.max(b))
+            }
+        };
+        let segments = (scale * bend / tolerance).sqrt().ceil() as usize;
+
+        Flatten {
+            curve: *self,
+            segments: segments.clamp(1, MAX_CURVE_SEGMENTS),
+            next: 0,
+        }
+    }
+}
+
+/// Iterator over the points of a flattened `Bezier` curve
+#[derive(Clone, Debug)]
+pub struct Flatten {
+    curve: Bezier,
+    segments: usize,
+    next: usize,
+}
+
+impl Iterator for Flatten {
+    type Item = Vec2;
+
+    fn next(&mut self) -> Option<Vec2> {
+        if self.next > self.segments {
+            return None;
+        }
+
+        let t = self.next as f32 / self.segments as f32;
+        self.next += 1;
+        Some(self.curve.point(t))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.segments + 1).saturating_sub(self.next);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Flatten {}
+
+/// Axis-aligned rectangle
+///
+/// `min` is always the upper left corner and `max` the lower right corner
+/// when the rectangle is built with one of its constructors.  A pair of
+/// `Vec2` corners in any order can be used wherever a `Rect` is expected.
+///
+/// ```
+/// use immediate_mode::{Rect, Vec2};
+///
+/// let rect: Rect = (Vec2::new(10.0, 0.0), Vec2::new(0.0, 10.0)).into();
+/// assert_eq!(rect.min, Vec2::new(0.0, 0.0));
+/// assert_eq!(rect.max, Vec2::new(10.0, 10.0));
+/// assert!(rect.contains(Vec2::new(5.0, 5.0)));
+/// ```
+#[derive(PartialEq, Copy, Clone, Debug, Default)]
+pub struct Rect {
+    /// Upper left corner
+    pub min: Vec2,
+    /// Lower right corner
+    pub max: Vec2,
+}
+
+impl Rect {
+    /// Rectangle spanning two opposite corners given in any order
+    pub fn new(a: Vec2, b: Vec2) -> Self {
+        Rect {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
+    /// Rectangle with its upper left corner at `origin`
+    pub fn from_origin_size(origin: Vec2, size: Vec2) -> Self {
+        Rect::new(origin, origin + size)
+    }
+
+    /// Rectangle centered on `center`
+    pub fn from_center_size(center: Vec2, size: Vec2) -> Self {
+        let half = size * 0.5;
+        Rect::new(center - half, center + half)
+    }
+
+    /// Horizontal extent
+    #[inline]
+    pub fn width(&self) -> f32 {
+        self.max.x - self.min.x
+    }
+
+    /// Vertical extent
+    #[inline]
+    pub fn height(&self) -> f32 {
+        self.max.y - self.min.y
+    }
+
+    /// Width and height
+    #[inline]
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    /// Point halfway between the corners
+    #[inline]
+    pub fn center(&self) -> Vec2 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Is the point inside the rectangle?
+    ///
+    /// The upper and left edges are inside, the lower and right edges are
+    /// not, so rectangles which share an edge never both contain a point.
+    #[inline]
+    pub fn contains(&self, pos: Vec2) -> bool {
+        self.min.x <= pos.x && pos.x < self.max.x && self.min.y <= pos.y && pos.y < self.max.y
+    }
+
+    /// Area covered by both rectangles, if any
+    ///
+    /// ```
+    /// use immediate_mode::{Rect, Vec2};
+    ///
+    /// let a = Rect::new(Vec2::new(0.0, 0.0), Vec2::new(10.0, 10.0));
+    /// let b = Rect::new(Vec2::new(5.0, 5.0), Vec2::new(15.0, 15.0));
+    /// let c = Rect::new(Vec2::new(20.0, 20.0), Vec2::new(30.0, 30.0));
+    ///
+    /// assert_eq!(a.intersect(b), Some(Rect::new(Vec2::new(5.0, 5.0), Vec2::new(10.0, 10.0))));
+    /// assert_eq!(a.intersect(c), None);
+    /// ```
+    pub fn intersect(&self, other: Rect) -> Option<Rect> {
+        let min = self.min.max(other.min);
+        let max = self.max.min(other.max);
+        if min.x < max.x && min.y < max.y {
+            Some(Rect { min, max })
+        } else {
+            None
+        }
+    }
+
+    /// Smallest rectangle containing both rectangles
+    pub fn union(&self, other: Rect) -> Rect {
+        Rect {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    /// Grow every edge outward by `amount`
+    pub fn expand(&self, amount: f32) -> Rect {
+        Rect::new(self.min - amount, self.max + amount)
+    }
+
+    /// Move every edge inward by `amount`
+    ///
+    /// Shrinking past the center collapses the rectangle onto its center.
+    pub fn shrink(&self, amount: f32) -> Rect {
+        let center = self.center();
+        Rect {
+            min: (self.min + amount).min(center),
+            max: (self.max - amount).max(center),
+        }
+    }
+
+    /// Move the rectangle by `offset`
+    pub fn translate(&self, offset: Vec2) -> Rect {
+        Rect {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+}
+
+// Conversion to and from primitive types
+
+impl From<(Vec2, Vec2)> for Rect {
+    fn from((a, b): (Vec2, Vec2)) -> Self {
+        Rect::new(a, b)
+    }
+}
+
+impl From<Rect> for (Vec2, Vec2) {
+    fn from(r: Rect) -> (Vec2, Vec2) {
+        (r.min, r.max)
+    }
+}
+
 #[cfg(test)]
 mod test {
     #[test]
@@ -187,4 +500,62 @@ mod test {
         let b: (f32, f32) = (a * 2.0).into();
         assert_eq!(b, (2.0, 2.0));
     }
+
+    #[test]
+    fn flattened_curves_stay_within_tolerance() {
+        use super::{Bezier, Vec2};
+
+        let curve = Bezier::Quadratic {
+            from: Vec2::new(0.0, 0.0),
+            ctrl: Vec2::new(50.0, 100.0),
+            to: Vec2::new(100.0, 0.0),
+        };
+        let points: Vec<_> = curve.flatten(0.25).collect();
+        assert_eq!(points[0], Vec2::new(0.0, 0.0));
+        assert_eq!(*points.last().unwrap(), Vec2::new(100.0, 0.0));
+        assert!(points.len() > curve.flatten(2.0).len());
+
+        // the middle of each segment is close to the curve
+        let segments = points.len() - 1;
+        for (i, pair) in points.windows(2).enumerate() {
+            let middle = (pair[0] + pair[1]) * 0.5;
+            let t = (i as f32 + 0.5) / segments as f32;
+            assert!((curve.point(t) - middle).len() <= 0.25);
+        }
+
+        // straight curves need a single segment
+        let line = Bezier::Cubic {
+            from: Vec2::new(0.0, 0.0),
+            ctrl1: Vec2::new(1.0, 1.0),
+            ctrl2: Vec2::new(2.0, 2.0),
+            to: Vec2::new(3.0, 3.0),
+        };
+        assert_eq!(line.flatten(0.25).len(), 2);
+    }
+
+    #[test]
+    fn arc_segments_grow_with_radius() {
+        use super::arc_segments;
+        use std::f32::consts::PI;
+
+        let small = arc_segments(2.0, 2.0 * PI, 0.25);
+        let large = arc_segments(200.0, 2.0 * PI, 0.25);
+        assert!(small < large);
+        assert_eq!(arc_segments(200.0, 0.0, 0.25), 1);
+        assert!(arc_segments(200.0, PI, 0.25) * 2 >= large);
+    }
+
+    #[test]
+    fn rect_shrink_and_expand() {
+        use super::{Rect, Vec2};
+
+        let rect = Rect::from_center_size(Vec2::new(5.0, 5.0), Vec2::new(10.0, 4.0));
+        assert_eq!(rect.expand(1.0).size(), Vec2::new(12.0, 6.0));
+        assert_eq!(rect.shrink(1.0).size(), Vec2::new(8.0, 2.0));
+
+        // shrinking collapses onto the center rather than flipping
+        let collapsed = rect.shrink(3.0);
+        assert_eq!(collapsed.height(), 0.0);
+        assert_eq!(collapsed.center(), rect.center());
+    }
 }
@@ -0,0 +1,89 @@
+//! Per-element state which persists between frames
+
+use crate::ID;
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+/// An entry in the store along with whether it was used this frame
+struct Entry {
+    value: Box<dyn Any>,
+    touched: bool,
+}
+
+/// Typed storage keyed by element `ID`
+///
+/// Each element can store one value of each type.  Entries which are not
+/// accessed during a frame are dropped when the frame finishes.  Values do
+/// not need to be `Clone`, so a clone of the store starts out empty.
+#[derive(Default)]
+pub(crate) struct StateStore {
+    entries: HashMap<(ID, TypeId), Entry>,
+}
+
+impl Clone for StateStore {
+    fn clone(&self) -> Self {
+        StateStore::default()
+    }
+}
+
+impl StateStore {
+    /// Access the state of type `T` for an element, creating it if necessary
+    pub(crate) fn get<T: Any + Default>(&mut self, id: ID) -> &mut T {
+        let entry = self
+            .entries
+            .entry((id, TypeId::of::<T>()))
+            .or_insert_with(|| Entry {
+                value: Box::new(T::default()),
+                touched: false,
+            });
+        entry.touched = true;
+
+        entry
+            .value
+            .downcast_mut::<T>()
+            .expect("state entry is keyed by its own type")
+    }
+
+    /// Remove the state of type `T` for an element
+    pub(crate) fn remove<T: Any>(&mut self, id: ID) {
+        self.entries.remove(&(id, TypeId::of::<T>()));
+    }
+
+    /// Drop entries which were not accessed since the last collection
+    pub(crate) fn collect_garbage(&mut self) {
+        self.entries.retain(|_, entry| entry.touched);
+        for entry in self.entries.values_mut() {
+            entry.touched = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::StateStore;
+
+    /// State which cannot be cloned
+    #[derive(Default)]
+    struct Handle(u32);
+
+    #[test]
+    fn state_persists_while_used() {
+        let mut store = StateStore::default();
+        store.get::<Handle>(2).0 = 7;
+        assert_eq!(store.get::<Handle>(2).0, 7);
+
+        *store.get::<u32>(1) += 1;
+        *store.get::<bool>(1) = true;
+        store.collect_garbage();
+        assert_eq!(store.entries.len(), 3);
+
+        *store.get::<u32>(1) += 1;
+        assert_eq!(*store.get::<u32>(1), 2);
+        store.collect_garbage();
+
+        // the bool and handle were not touched last frame
+        assert_eq!(store.entries.len(), 1);
+        assert!(!*store.get::<bool>(1));
+    }
+}
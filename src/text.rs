@@ -1,7 +1,18 @@
 //! Loading fonts to the GPU for immediate-mode to use
 
+mod atlas;
+mod fonts;
+mod layout;
+mod sdf;
+
+use crate::Vec2;
+
 use std::ops::{Index, IndexMut};
+use std::str::Chars;
 
+pub use self::atlas::{AtlasGlyph, AtlasUpdate, FontAtlas, FontId, GlyphMode};
+pub use self::fonts::{FontFamily, Fonts};
+pub use self::layout::{BlockGlyph, BlockLine, HAlign, TextBlock, TextOptions, VAlign};
 pub use rusttype::*;
 
 /// 2D grayscale texture
@@ -39,6 +50,23 @@ impl Texture {
         }
     }
 
+    /// Change the size of the texture, keeping pixels where they are
+    ///
+    /// Pixels outside of the new size are dropped and new pixels are zero.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        let mut pixels = vec![0u8; width * height];
+        let copy_width = self.width.min(width);
+        for y in 0..self.height.min(height) {
+            let src = y * self.width;
+            let dst = y * width;
+            pixels[dst..dst + copy_width].copy_from_slice(&self.pixels[src..src + copy_width]);
+        }
+
+        self.width = width;
+        self.height = height;
+        self.pixels = pixels;
+    }
+
     /// Width and height of the texture
     pub fn dimensions(&self) -> (u32, u32) {
         (self.width as u32, self.height as u32)
@@ -51,3 +79,190 @@ impl Texture {
         self.pixels.as_slice()
     }
 }
+
+/// Vertical metrics shared by every line of text in a font at a size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineMetrics {
+    /// Distance from the baseline to the top of the tallest glyphs
+    pub ascent: f32,
+    /// Distance from the baseline to the bottom of the lowest glyphs,
+    /// usually negative
+    pub descent: f32,
+    /// Extra space between the descent of one line and the ascent of the next
+    pub line_gap: f32,
+}
+
+impl LineMetrics {
+    /// Distance between the baselines of consecutive lines
+    pub fn height(&self) -> f32 {
+        self.ascent - self.descent + self.line_gap
+    }
+}
+
+/// Vertical metrics of a font at a pixel size
+pub fn line_metrics(font: &Font<'_>, size: f32) -> LineMetrics {
+    let v_metrics = font.v_metrics(Scale::uniform(size));
+    LineMetrics {
+        ascent: v_metrics.ascent,
+        descent: v_metrics.descent,
+        line_gap: v_metrics.line_gap,
+    }
+}
+
+/// Horizontal distance a character moves the pen, without kerning
+pub fn advance(font: &Font<'_>, size: f32, c: char) -> f32 {
+    font.glyph(c)
+        .scaled(Scale::uniform(size))
+        .h_metrics()
+        .advance_width
+}
+
+/// Width and height of a string drawn at a pixel size
+///
+/// The width is that of the widest line including kerning; the height covers
+/// every line, so a trailing newline adds an empty line.
+pub fn measure_text(font: &Font<'static>, size: f32, text: &str) -> Vec2 {
+    measure_chain(&[font], size, text)
+}
+
+/// Measure text drawn with the first font of a fallback chain which has
+/// each glyph
+pub(crate) fn measure_chain(fonts: &[&Font<'static>], size: f32, text: &str) -> Vec2 {
+    let width = GlyphLayout::new(fonts, size, Vec2::zero(), text)
+        .map(|glyph| glyph.origin.x + glyph.advance)
+        .fold(0.0, f32::max);
+    let lines = text.split('\n').count() as f32;
+    let line_height = fonts
+        .first()
+        .map_or(0.0, |font| line_metrics(font, size).height());
+    Vec2::new(width, lines * line_height)
+}
+
+/// A glyph placed by `GlyphLayout`
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LaidGlyph {
+    /// Index of the font in the layout's fallback chain
+    pub font: usize,
+    /// Glyph to draw
+    pub id: GlyphId,
+    /// Position of the glyph's origin on the baseline
+    pub origin: Vec2,
+    /// Horizontal distance to the next glyph's origin, without kerning
+    pub advance: f32,
+}
+
+/// Positions the glyphs of a string along lines of text
+///
+/// Each character is drawn with the first font in the chain which has a
+/// glyph for it, or the first font if none do; line heights come from the
+/// first font.  Glyphs are advanced by their advance widths and kerning;
+/// explicit newlines start a new line below the previous one.
+#[derive(Clone)]
+pub(crate) struct GlyphLayout<'a> {
+    fonts: &'a [&'a Font<'static>],
+    scale: Scale,
+    chars: Chars<'a>,
+    left: f32,
+    pen: Vec2,
+    line_height: f32,
+    last: Option<(usize, GlyphId)>,
+}
+
+impl<'a> GlyphLayout<'a> {
+    /// Lay out text with `pos` at the upper left corner of the first line
+    pub fn new(fonts: &'a [&'a Font<'static>], size: f32, pos: Vec2, text: &'a str) -> Self {
+        let (ascent, line_height) = fonts.first().map_or((0.0, 0.0), |font| {
+            let metrics = line_metrics(font, size);
+            (metrics.ascent, metrics.height())
+        });
+
+        // without fonts there is nothing to lay out
+        let text = if fonts.is_empty() { "" } else { text };
+
+        GlyphLayout {
+            fonts,
+            scale: Scale::uniform(size),
+            chars: text.chars(),
+            left: pos.x,
+            pen: Vec2::new(pos.x, pos.y + ascent),
+            line_height,
+            last: None,
+        }
+    }
+}
+
+impl<'a> Iterator for GlyphLayout<'a> {
+    type Item = LaidGlyph;
+
+    fn next(&mut self) -> Option<LaidGlyph> {
+        loop {
+            let c = self.chars.next()?;
+            if c == '\n' {
+                self.pen = Vec2::new(self.left, self.pen.y + self.line_height);
+                self.last = None;
+                continue;
+            }
+
+            // glyph id zero is the font's placeholder for missing glyphs
+            let font = self
+                .fonts
+                .iter()
+                .position(|font| font.glyph(c).id().0 != 0)
+                .unwrap_or(0);
+            let glyph = self.fonts[font].glyph(c).scaled(self.scale);
+            let id = glyph.id();
+
+            // only glyphs of the same font kern
+            if let Some((last_font, last)) = self.last {
+                if last_font == font {
+                    self.pen.x += self.fonts[font].pair_kerning(self.scale, last, id);
+                }
+            }
+
+            let advance = glyph.h_metrics().advance_width;
+            let origin = self.pen;
+            self.pen.x += advance;
+            self.last = Some((font, id));
+
+            return Some(LaidGlyph {
+                font,
+                id,
+                origin,
+                advance,
+            });
+        }
+    }
+}
+
+/// Fonts with Source Sans Pro as the proportional family
+#[cfg(test)]
+pub(crate) fn test_fonts() -> Fonts {
+    let data = include_bytes!("../dev/fonts/Source/SourceSansPro-Regular.ttf");
+    let mut fonts = Fonts::new(256, 256);
+    let sans = fonts.add_font(Font::from_bytes(&data[..]).unwrap(), GlyphMode::Coverage);
+    fonts.push_font(FontFamily::PROPORTIONAL, sans);
+    fonts
+}
+
+#[cfg(test)]
+mod test {
+    use super::{advance, line_metrics, measure_text, test_fonts, FontFamily};
+
+    #[test]
+    fn measure_lines_and_multibyte_text() {
+        let fonts = test_fonts();
+        let font = fonts.faces(FontFamily::PROPORTIONAL).1[0];
+        let line_height = line_metrics(font, 20.0).height();
+
+        let single = measure_text(font, 20.0, "é");
+        assert_eq!(single.x, advance(font, 20.0, 'é'));
+        assert_eq!(single.y, line_height);
+
+        // the widest line sets the width
+        let double = measure_text(font, 20.0, "éé\né");
+        assert!(double.x > single.x);
+        assert_eq!(double.y, 2.0 * line_height);
+
+        assert_eq!(measure_text(font, 20.0, "").x, 0.0);
+    }
+}
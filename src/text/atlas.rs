@@ -0,0 +1,531 @@
+//! Glyph atlas which rasterizes glyphs into a `Texture` on demand
+
+use super::{point, Font, GlyphId, Scale, Texture};
+use crate::{Rect, Vec2};
+
+use std::collections::HashMap;
+
+/// Size in texels of the white block reserved at `OPAQUE_UV`
+const WHITE_TEXELS: u32 = 2;
+
+/// Padding in texels between glyphs so filtering does not bleed
+const PADDING: u32 = 1;
+
+/// Largest size the texture grows to by default
+const DEFAULT_MAX_SIZE: usize = 4096;
+
+/// How glyphs are rasterized into the atlas
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum GlyphMode {
+    /// Coverage rasterized separately for every size drawn
+    ///
+    /// Sharpest at the sizes drawn, but each size takes its own space in the
+    /// atlas.  Renderers use the texture as the alpha of a white texel.
+    #[default]
+    Coverage,
+    /// A signed distance field rasterized once at `size` and scaled to every
+    /// size drawn
+    ///
+    /// Texels store the distance to the glyph's edge: `0.5` lies on the edge
+    /// and the value changes by `0.5` over `spread` texels, increasing
+    /// inside.  Renderers threshold the texture at `0.5` and smooth the edge
+    /// over about one pixel on screen, for example in GLSL:
+    ///
+    /// ```glsl
+    /// float distance = texture(u_texture, f_uv).r;
+    /// float smoothing = fwidth(distance) * 0.5;
+    /// float alpha = smoothstep(0.5 - smoothing, 0.5 + smoothing, distance);
+    /// color = vec4(f_color.rgb, f_color.a * alpha);
+    /// ```
+    ///
+    /// The white block at `OPAQUE_UV` is fully inside, so solid shapes draw
+    /// with the same shader.
+    Sdf {
+        /// Pixel size glyphs are rasterized at, at least one pixel
+        size: f32,
+        /// Distance in texels covered by the field on each side of the edge,
+        /// at least one texel
+        spread: f32,
+    },
+}
+
+/// Part of the atlas texture which must be uploaded again
+#[derive(Debug, Clone, PartialEq)]
+pub enum AtlasUpdate {
+    /// The texture was created, resized or cleared; upload all of it
+    Full,
+    /// Only these rectangles, in texels, changed
+    Partial(Vec<Rect>),
+}
+
+/// Progress of making room in a full atlas
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Overflow {
+    /// The atlas has room
+    None,
+    /// A glyph of this size in texels did not fit this frame; the texture
+    /// must not change until the glyphs drawn this frame have been rendered
+    Pending(u32, u32),
+    /// The frame ended, so the texture can grow or evict every glyph
+    Ready(u32, u32),
+}
+
+/// Handle to a font added to a `FontAtlas`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FontId(usize);
+
+/// A glyph which has been rasterized into the atlas
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtlasGlyph {
+    /// Texel coordinates of the glyph in the atlas texture
+    pub texels: Rect,
+    /// Pixel bounds of the glyph relative to its origin on the baseline
+    pub bounds: Rect,
+}
+
+/// Fonts along with a texture of their rasterized glyphs
+///
+/// Glyphs of every font share one texture.  They are rasterized the first
+/// time they are drawn at a size and packed into rows of the texture.  The
+/// texture keeps a white block at its upper left corner so it can be used as
+/// `TextureId::DEFAULT` for solid shapes.
+///
+/// A full atlas drops glyphs for the rest of the frame, so texture
+/// coordinates of the glyphs already drawn stay valid.  Once the frame's
+/// updates have been taken the texture doubles in size until the dropped
+/// glyph fits.  At its maximum size it evicts every glyph instead, so the next
+/// frame rasterizes only the glyphs it uses.
+///
+/// The texture stores coverage or a distance field depending on the
+/// `GlyphMode` of each font.  Call `take_update` once per frame, after
+/// drawing, to find which parts of the texture to upload.
+#[derive(Debug, Clone)]
+pub struct FontAtlas {
+    /// Fonts indexed by `FontId` and how their glyphs are rasterized
+    fonts: Vec<(Font<'static>, GlyphMode)>,
+    texture: Texture,
+    /// Rasterized glyphs keyed by font, glyph and size, `None` for glyphs
+    /// which have nothing to draw, like spaces
+    glyphs: HashMap<(FontId, GlyphId, u32), Option<AtlasGlyph>>,
+    /// Position of the next glyph in the current row
+    cursor: (u32, u32),
+    /// Height of the tallest glyph in the current row
+    row_height: u32,
+    /// Largest width and height the texture may grow to
+    max_size: usize,
+    /// Changes to the texture since it was last uploaded
+    update: Option<AtlasUpdate>,
+    overflow: Overflow,
+}
+
+impl FontAtlas {
+    /// Create an atlas without any fonts with a texture of the given size
+    ///
+    /// The texture is at least large enough for its white block.
+    pub fn new(width: usize, height: usize) -> Self {
+        let white = WHITE_TEXELS as usize;
+        let (width, height) = (width.max(white), height.max(white));
+        let mut atlas = FontAtlas {
+            fonts: Vec::new(),
+            texture: Texture::new(width, height),
+            glyphs: HashMap::new(),
+            cursor: (0, 0),
+            row_height: 0,
+            max_size: DEFAULT_MAX_SIZE.max(width).max(height),
+            update: None,
+            overflow: Overflow::None,
+        };
+        atlas.clear();
+        atlas
+    }
+
+    /// Limit how large the texture may grow
+    ///
+    /// The texture never shrinks, so a limit below its current size keeps the
+    /// current size.
+    pub fn set_max_size(&mut self, max_size: usize) {
+        let (width, height) = self.texture.dimensions();
+        self.max_size = max_size.max(width as usize).max(height as usize);
+    }
+
+    /// Add a font whose glyphs are rasterized with a `GlyphMode`
+    ///
+    /// Distance field sizes and spreads below one are raised to one.
+    pub fn add_font(&mut self, font: Font<'static>, mode: GlyphMode) -> FontId {
+        let mode = match mode {
+            GlyphMode::Sdf { size, spread } => GlyphMode::Sdf {
+                size: size.max(1.0),
+                spread: spread.max(1.0),
+            },
+            mode => mode,
+        };
+        self.fonts.push((font, mode));
+        FontId(self.fonts.len() - 1)
+    }
+
+    /// A font added to the atlas
+    pub fn font(&self, font: FontId) -> &Font<'static> {
+        &self.fonts[font.0].0
+    }
+
+    /// How a font's glyphs are rasterized
+    pub fn mode(&self, font: FontId) -> GlyphMode {
+        self.fonts[font.0].1
+    }
+
+    /// Texture containing the rasterized glyphs
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    /// Changes to the texture since the last call, if any
+    ///
+    /// Call once per frame after drawing and upload the changed parts of the
+    /// texture before rendering the frame.
+    pub fn take_update(&mut self) -> Option<AtlasUpdate> {
+        if let Overflow::Pending(width, height) = self.overflow {
+            self.overflow = Overflow::Ready(width, height);
+        }
+        self.update.take()
+    }
+
+    /// Find a glyph of a font at a pixel size, rasterizing it if necessary
+    ///
+    /// Returns `None` for glyphs with nothing to draw, for glyphs which do
+    /// not fit in the texture until the frame ends and for glyphs too large
+    /// for the texture at its maximum size.
+    pub fn glyph(&mut self, font: FontId, id: GlyphId, size: f32) -> Option<AtlasGlyph> {
+        if let Overflow::Ready(width, height) = self.overflow {
+            // the last frame has been rendered, so its texture coordinates
+            // are no longer used
+            self.overflow = Overflow::None;
+            while self.place(width, height).is_none() {
+                if !self.grow() {
+                    self.clear();
+                    break;
+                }
+            }
+        }
+
+        // distance fields are rasterized at one size and scaled
+        let (face, mode) = &self.fonts[font.0];
+        let sdf = matches!(mode, GlyphMode::Sdf { .. });
+        let (raster_size, spread) = match *mode {
+            GlyphMode::Coverage => (size, 0.0),
+            GlyphMode::Sdf { size, spread } => (size, spread),
+        };
+        let scale = size / raster_size;
+
+        let key = (font, id, raster_size.to_bits());
+        if let Some(glyph) = self.glyphs.get(&key) {
+            return glyph.map(|glyph| AtlasGlyph {
+                bounds: scale_rect(glyph.bounds, scale),
+                ..glyph
+            });
+        }
+
+        let glyph = face
+            .glyph(id)
+            .scaled(Scale::uniform(raster_size))
+            .positioned(point(0.0, 0.0));
+        let bb = match glyph.pixel_bounding_box() {
+            Some(bb) => bb,
+            None => {
+                self.glyphs.insert(key, None);
+                return None;
+            }
+        };
+
+        // leave room around the glyph for the field outside its edges
+        let margin = spread.ceil() as u32;
+        let (width, height) = (
+            bb.width() as u32 + 2 * margin,
+            bb.height() as u32 + 2 * margin,
+        );
+        if !self.fits_when_empty(width, height) {
+            // making room would never help
+            self.glyphs.insert(key, None);
+            return None;
+        }
+        let (x, y) = match self.allocate(width, height) {
+            Some(position) => position,
+            None => {
+                // make room once the glyphs drawn this frame have been used
+                if self.overflow == Overflow::None {
+                    self.overflow = Overflow::Pending(width, height);
+                }
+                return None;
+            }
+        };
+        let mut coverage = Texture::new(width as usize, height as usize);
+        glyph.draw(|gx, gy, v| {
+            coverage[(margin + gx, margin + gy)] = (v * 255.0) as u8;
+        });
+        if sdf {
+            coverage = coverage.to_distance_field(spread);
+        }
+        for gy in 0..height {
+            for gx in 0..width {
+                self.texture[(x + gx, y + gy)] = coverage[(gx, gy)];
+            }
+        }
+
+        let texels = Rect::new(
+            Vec2::new(x as f32, y as f32),
+            Vec2::new((x + width) as f32, (y + height) as f32),
+        );
+        self.mark_dirty(texels);
+
+        let atlas_glyph = AtlasGlyph {
+            texels,
+            bounds: Rect::new(
+                Vec2::new(bb.min.x as f32, bb.min.y as f32),
+                Vec2::new(bb.max.x as f32, bb.max.y as f32),
+            )
+            .expand(margin as f32),
+        };
+        self.glyphs.insert(key, Some(atlas_glyph));
+        Some(AtlasGlyph {
+            bounds: scale_rect(atlas_glyph.bounds, scale),
+            ..atlas_glyph
+        })
+    }
+
+    /// Texture coordinates of a glyph
+    pub fn uv(&self, glyph: &AtlasGlyph) -> Rect {
+        let (width, height) = self.texture.dimensions();
+        let size = Vec2::new(1.0 / width as f32, 1.0 / height as f32);
+        Rect {
+            min: Vec2::new(glyph.texels.min.x * size.x, glyph.texels.min.y * size.y),
+            max: Vec2::new(glyph.texels.max.x * size.x, glyph.texels.max.y * size.y),
+        }
+    }
+
+    /// Evict every glyph, leaving only the white block
+    fn clear(&mut self) {
+        let (width, height) = self.texture.dimensions();
+        self.texture = Texture::new(width as usize, height as usize);
+        for y in 0..WHITE_TEXELS {
+            for x in 0..WHITE_TEXELS {
+                self.texture[(x, y)] = 255;
+            }
+        }
+
+        self.glyphs.clear();
+        self.cursor = (WHITE_TEXELS + PADDING, 0);
+        self.row_height = WHITE_TEXELS;
+        self.update = Some(AtlasUpdate::Full);
+        self.overflow = Overflow::None;
+    }
+
+    /// Record that part of the texture must be uploaded again
+    fn mark_dirty(&mut self, texels: Rect) {
+        match &mut self.update {
+            Some(AtlasUpdate::Full) => (),
+            Some(AtlasUpdate::Partial(rects)) => match rects.last_mut() {
+                // glyphs in the same row are merged into one upload
+                Some(last) if last.min.y == texels.min.y => *last = last.union(texels),
+                _ => rects.push(texels),
+            },
+            None => self.update = Some(AtlasUpdate::Partial(vec![texels])),
+        }
+    }
+
+    /// Take space for a glyph
+    fn allocate(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let (position, row_height) = self.place(width, height)?;
+        self.cursor = (position.0 + width + PADDING, position.1);
+        self.row_height = row_height.max(height);
+        Some(position)
+    }
+
+    /// Where a glyph fits in the current row, or in a new row, along with the
+    /// height of that row so far
+    fn place(&self, width: u32, height: u32) -> Option<((u32, u32), u32)> {
+        let (texture_width, texture_height) = self.texture.dimensions();
+
+        let (mut position, mut row_height) = (self.cursor, self.row_height);
+        if position.0 + width > texture_width {
+            position = (0, position.1 + row_height + PADDING);
+            row_height = 0;
+        }
+        if position.0 + width > texture_width || position.1 + height > texture_height {
+            return None;
+        }
+        Some((position, row_height))
+    }
+
+    /// Whether a glyph fits in an atlas of the maximum size without any other
+    /// glyphs, beside or below the white block
+    fn fits_when_empty(&self, width: u32, height: u32) -> bool {
+        let (max, white) = (self.max_size as u32, WHITE_TEXELS + PADDING);
+        (width + white <= max && height <= max) || (width <= max && height + white <= max)
+    }
+
+    /// Double the height, or the width once the texture is taller than it is
+    /// wide, keeping every glyph where it is
+    fn grow(&mut self) -> bool {
+        let (width, height) = self.texture.dimensions();
+        let (width, height) = (width as usize, height as usize);
+        let (new_width, new_height) = if height < width {
+            (width, (height * 2).min(self.max_size))
+        } else {
+            ((width * 2).min(self.max_size), height)
+        };
+        if (new_width, new_height) == (width, height) {
+            return false;
+        }
+
+        self.texture.resize(new_width, new_height);
+        self.update = Some(AtlasUpdate::Full);
+        true
+    }
+}
+
+/// Scale a rectangle about the origin
+fn scale_rect(rect: Rect, scale: f32) -> Rect {
+    Rect {
+        min: rect.min * scale,
+        max: rect.max * scale,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AtlasUpdate, FontAtlas, FontId, GlyphMode};
+    use crate::draw::{DrawData, Vert};
+    use crate::text::{test_fonts, FontFamily, Fonts};
+    use crate::{Color, Vec2};
+
+    fn atlas(mode: GlyphMode) -> (FontAtlas, FontId) {
+        let face = test_fonts().faces(FontFamily::PROPORTIONAL).1[0].clone();
+        let mut atlas = FontAtlas::new(128, 128);
+        let font = atlas.add_font(face, mode);
+        (atlas, font)
+    }
+
+    #[test]
+    fn glyphs_are_cached() {
+        let (mut atlas, font) = atlas(GlyphMode::Coverage);
+        assert_eq!(atlas.texture()[(0, 0)], 255);
+
+        let id = atlas.font(font).glyph('A').id();
+        assert_eq!(atlas.take_update(), Some(AtlasUpdate::Full));
+        let first = atlas.glyph(font, id, 20.0).unwrap();
+        assert_eq!(
+            atlas.take_update(),
+            Some(AtlasUpdate::Partial(vec![first.texels]))
+        );
+
+        // the second lookup does not touch the texture
+        assert_eq!(atlas.glyph(font, id, 20.0), Some(first));
+        assert_eq!(atlas.take_update(), None);
+
+        // spaces have nothing to rasterize
+        let space = atlas.font(font).glyph(' ').id();
+        assert_eq!(atlas.glyph(font, space, 20.0), None);
+    }
+
+    #[test]
+    fn full_atlas_grows_then_evicts() {
+        let (mut atlas, font) = atlas(GlyphMode::Coverage);
+        atlas.set_max_size(256);
+        let id = atlas.font(font).glyph('W').id();
+
+        // fill the atlas with the same glyph at many sizes, ending the frame
+        // whenever it is full so the texture can grow
+        let mut size = 20.0;
+        loop {
+            while atlas.glyph(font, id, size).is_some() {
+                size += 1.0;
+            }
+            if atlas.texture().dimensions() == (256, 256) {
+                break;
+            }
+            atlas.take_update();
+        }
+
+        // glyphs which did not fit come back after the frame ends
+        atlas.take_update();
+        assert!(atlas.glyph(font, id, size).is_some());
+        assert_eq!(atlas.take_update(), Some(AtlasUpdate::Full));
+    }
+
+    #[test]
+    fn distance_fields_are_shared_between_sizes() {
+        let (mut atlas, font) = atlas(GlyphMode::Sdf {
+            size: 32.0,
+            spread: 4.0,
+        });
+        let id = atlas.font(font).glyph('A').id();
+
+        let small = atlas.glyph(font, id, 16.0).unwrap();
+        let large = atlas.glyph(font, id, 64.0).unwrap();
+        assert_eq!(small.texels, large.texels);
+        assert_eq!(large.bounds.width(), small.bounds.width() * 4.0);
+    }
+
+    #[test]
+    fn texture_grows_after_the_frame() {
+        let mut fonts = test_fonts();
+        let font = fonts.faces(FontFamily::PROPORTIONAL).0[0];
+
+        let mut draw_data = DrawData::<Vert>::default();
+        let white = Color(0xFF_FF_FF_FF);
+        draw_data.text(
+            &mut fonts,
+            FontFamily::PROPORTIONAL,
+            20.0,
+            Vec2::zero(),
+            white,
+            "A",
+        );
+        let (_, first_uv, _) = draw_data.verts()[0];
+
+        // fill the atlas later in the same frame
+        let atlas = fonts.atlas_mut();
+        let id = atlas.font(font).glyph('W').id();
+        let mut size = 20.0;
+        while atlas.glyph(font, id, size).is_some() {
+            size += 1.0;
+        }
+        assert_eq!(atlas.texture().dimensions(), (256, 256));
+
+        // the quad drawn first still samples its glyph
+        let a = atlas.font(font).glyph('A').id();
+        let glyph = atlas.glyph(font, a, 20.0).unwrap();
+        let uv = atlas.uv(&glyph);
+        assert_eq!(first_uv, [uv.min.x, uv.min.y]);
+
+        atlas.take_update();
+        assert!(atlas.glyph(font, id, size).is_some());
+        assert_eq!(atlas.texture().dimensions(), (512, 256));
+        assert_eq!(atlas.take_update(), Some(AtlasUpdate::Full));
+    }
+
+    #[test]
+    fn glyphs_too_large_for_the_atlas_are_skipped() {
+        let (mut atlas, font) = atlas(GlyphMode::Coverage);
+        atlas.set_max_size(256);
+        let id = atlas.font(font).glyph('W').id();
+        let small = atlas.glyph(font, id, 20.0).unwrap();
+        atlas.take_update();
+
+        // nothing is evicted to make room which would never be enough
+        for _ in 0..3 {
+            assert_eq!(atlas.glyph(font, id, 600.0), None);
+            assert_eq!(atlas.take_update(), None);
+        }
+        assert_eq!(atlas.glyph(font, id, 20.0), Some(small));
+    }
+
+    #[test]
+    fn tiny_atlases_keep_their_white_block() {
+        for size in 0..3 {
+            let atlas = FontAtlas::new(size, size);
+            assert_eq!(atlas.texture().dimensions(), (2, 2));
+            assert_eq!(atlas.texture()[(1, 1)], 255);
+        }
+        Fonts::new(1, 1);
+    }
+}
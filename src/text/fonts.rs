@@ -0,0 +1,153 @@
+//! Collections of fonts grouped into families
+
+use super::{line_metrics, measure_chain, Font, FontAtlas, FontId, GlyphMode, LineMetrics};
+use crate::Vec2;
+
+/// Handle to a family of fonts in `Fonts`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FontFamily(usize);
+
+impl FontFamily {
+    /// Family for most text, named `"proportional"`
+    pub const PROPORTIONAL: FontFamily = FontFamily(0);
+    /// Family where every glyph has the same width, named `"monospace"`
+    pub const MONOSPACE: FontFamily = FontFamily(1);
+}
+
+/// A named chain of fonts
+#[derive(Debug, Clone)]
+struct Family {
+    name: String,
+    fonts: Vec<FontId>,
+}
+
+/// Fonts sharing one atlas, grouped into named families
+///
+/// Text is drawn with a family: each character uses the first font with a
+/// glyph for it, searching the family's fonts and then the fallback fonts
+/// shared by every family, so symbol or emoji fonts can fill in the gaps.
+///
+/// ```
+/// use immediate_mode::text::{Font, FontFamily, Fonts, GlyphMode};
+///
+/// let data = include_bytes!("../../dev/fonts/Source/SourceSansPro-Regular.ttf");
+/// let mut fonts = Fonts::new(256, 256);
+/// let sans = fonts.add_font(Font::from_bytes(&data[..]).unwrap(), GlyphMode::Coverage);
+/// fonts.push_font(FontFamily::PROPORTIONAL, sans);
+///
+/// // the monospace family has no fonts of its own, so it uses the fallbacks
+/// fonts.add_fallback(sans);
+/// assert!(fonts.measure_text(FontFamily::MONOSPACE, 16.0, "mono").x > 0.0);
+/// assert_eq!(fonts.family("monospace"), Some(FontFamily::MONOSPACE));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Fonts {
+    atlas: FontAtlas,
+    families: Vec<Family>,
+    fallbacks: Vec<FontId>,
+}
+
+impl Fonts {
+    /// Create empty proportional and monospace families with an atlas
+    /// texture of the given size
+    pub fn new(width: usize, height: usize) -> Self {
+        let family = |name: &str| Family {
+            name: name.to_owned(),
+            fonts: Vec::new(),
+        };
+
+        Fonts {
+            atlas: FontAtlas::new(width, height),
+            families: vec![family("proportional"), family("monospace")],
+            fallbacks: Vec::new(),
+        }
+    }
+
+    /// Load a font into the atlas
+    ///
+    /// The font is not used until it is added to a family or as a fallback.
+    pub fn add_font(&mut self, font: Font<'static>, mode: GlyphMode) -> FontId {
+        self.atlas.add_font(font, mode)
+    }
+
+    /// Create a family from a chain of fonts, or replace the fonts of the
+    /// family with the same name
+    pub fn add_family<S: Into<String>>(&mut self, name: S, fonts: Vec<FontId>) -> FontFamily {
+        let name = name.into();
+        match self.family(&name) {
+            Some(family) => {
+                self.families[family.0].fonts = fonts;
+                family
+            }
+            None => {
+                self.families.push(Family { name, fonts });
+                FontFamily(self.families.len() - 1)
+            }
+        }
+    }
+
+    /// Find a family by name
+    pub fn family(&self, name: &str) -> Option<FontFamily> {
+        self.families
+            .iter()
+            .position(|family| family.name == name)
+            .map(FontFamily)
+    }
+
+    /// Add a font to the end of a family's chain
+    pub fn push_font(&mut self, family: FontFamily, font: FontId) {
+        self.families[family.0].fonts.push(font);
+    }
+
+    /// Add a font consulted by every family after its own fonts
+    pub fn add_fallback(&mut self, font: FontId) {
+        self.fallbacks.push(font);
+    }
+
+    /// Fonts searched for glyphs when drawing with a family, in order
+    pub fn chain(&self, family: FontFamily) -> impl Iterator<Item = FontId> + '_ {
+        self.families[family.0]
+            .fonts
+            .iter()
+            .chain(self.fallbacks.iter())
+            .copied()
+    }
+
+    /// Atlas containing every font
+    pub fn atlas(&self) -> &FontAtlas {
+        &self.atlas
+    }
+
+    /// Mutably access the atlas, for example to take its updates
+    pub fn atlas_mut(&mut self) -> &mut FontAtlas {
+        &mut self.atlas
+    }
+
+    /// Vertical metrics of the first font of a family
+    ///
+    /// A family without any fonts has no height.
+    pub fn line_metrics(&self, family: FontFamily, size: f32) -> LineMetrics {
+        match self.chain(family).next() {
+            Some(font) => line_metrics(self.atlas.font(font), size),
+            None => LineMetrics {
+                ascent: 0.0,
+                descent: 0.0,
+                line_gap: 0.0,
+            },
+        }
+    }
+
+    /// Width and height of a string drawn with a family
+    ///
+    /// Measures like `text::measure_text` while falling back between fonts.
+    pub fn measure_text(&self, family: FontFamily, size: f32, text: &str) -> Vec2 {
+        measure_chain(&self.faces(family).1, size, text)
+    }
+
+    /// The fonts in a family's chain along with their ids
+    pub(crate) fn faces(&self, family: FontFamily) -> (Vec<FontId>, Vec<&Font<'static>>) {
+        self.chain(family)
+            .map(|font| (font, self.atlas.font(font)))
+            .unzip()
+    }
+}
@@ -0,0 +1,405 @@
+//! Laying out blocks of text within a rectangle
+
+use super::{measure_chain, Font, FontFamily, FontId, Fonts, GlyphId, GlyphLayout};
+use crate::{Rect, Vec2};
+
+use std::ops::Range;
+
+/// Horizontal alignment of lines within a text block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HAlign {
+    /// Lines start at the left edge
+    Left,
+    /// Lines are centered between the edges
+    Center,
+    /// Lines end at the right edge
+    Right,
+}
+
+/// Vertical alignment of the lines of a text block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VAlign {
+    /// The first line starts at the top edge
+    Top,
+    /// The lines are centered between the edges
+    Center,
+    /// The last line ends at the bottom edge
+    Bottom,
+}
+
+/// How to lay out a block of text
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextOptions {
+    /// Pixel size of the text
+    pub size: f32,
+    /// Alignment of each line
+    pub h_align: HAlign,
+    /// Alignment of the lines as a whole
+    pub v_align: VAlign,
+    /// Break lines which are wider than the block
+    pub wrap: bool,
+    /// Shorten text which does not fit in the block and end it with an
+    /// ellipsis
+    pub ellipsis: bool,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        TextOptions {
+            size: 16.0,
+            h_align: HAlign::Left,
+            v_align: VAlign::Top,
+            wrap: true,
+            ellipsis: false,
+        }
+    }
+}
+
+/// A glyph positioned within a text block
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlockGlyph {
+    /// Font the glyph is drawn with
+    pub font: FontId,
+    /// Glyph to draw
+    pub id: GlyphId,
+    /// Byte offset of the glyph's character in the text
+    ///
+    /// An ellipsis has the offset of the first character it replaces.
+    pub index: usize,
+    /// Position of the glyph's origin on the baseline
+    pub origin: Vec2,
+    /// Area covered by the glyph's advance and the height of its line
+    pub rect: Rect,
+}
+
+/// A line of a text block
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockLine {
+    /// Byte range of the text shown on this line
+    pub range: Range<usize>,
+    /// Range of `TextBlock::glyphs` on this line
+    pub glyphs: Range<usize>,
+    /// Area covered by the line's glyphs
+    pub rect: Rect,
+    /// Height of the baseline
+    pub baseline: f32,
+}
+
+/// Text laid out in lines within a rectangle
+///
+/// Lines are broken at explicit newlines and, when wrapping, between words,
+/// or between characters of words which are too wide to fit on their own.
+/// Text which overflows the block is kept unless it is truncated with an
+/// ellipsis.
+///
+/// ```
+/// use immediate_mode::text::{Font, FontFamily, Fonts, GlyphMode, TextBlock, TextOptions};
+/// use immediate_mode::{Rect, Vec2};
+///
+/// let data = include_bytes!("../../dev/fonts/Source/SourceSansPro-Regular.ttf");
+/// let mut fonts = Fonts::new(256, 256);
+/// let sans = fonts.add_font(Font::from_bytes(&data[..]).unwrap(), GlyphMode::Coverage);
+/// fonts.push_font(FontFamily::PROPORTIONAL, sans);
+/// let bounds = Rect::from_origin_size(Vec2::zero(), Vec2::new(60.0, 100.0));
+///
+/// let options = TextOptions::default();
+/// let text = "wrap these words";
+/// let block = TextBlock::new(&fonts, FontFamily::PROPORTIONAL, &options, bounds, text);
+/// assert!(block.lines.len() > 1);
+///
+/// // find the character under a point
+/// let first = &block.glyphs[0];
+/// assert_eq!(block.char_at(first.rect.center()), Some(0));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextBlock {
+    /// Pixel size of the text
+    pub size: f32,
+    /// Every glyph in the block, line by line
+    pub glyphs: Vec<BlockGlyph>,
+    /// Lines from top to bottom
+    pub lines: Vec<BlockLine>,
+}
+
+impl TextBlock {
+    /// Lay out text drawn with a font family within `bounds`
+    pub fn new(
+        fonts: &Fonts,
+        family: FontFamily,
+        options: &TextOptions,
+        bounds: Rect,
+        text: &str,
+    ) -> Self {
+        let (ids, faces) = fonts.faces(family);
+        let size = options.size;
+        let max_width = bounds.width();
+        let line_height = fonts.line_metrics(family, size).height();
+        let width = |range: &Range<usize>| measure_chain(&faces, size, &text[range.clone()]).x;
+
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        for paragraph in text.split('\n') {
+            let range = start..start + paragraph.len();
+            start = range.end + 1;
+            if options.wrap {
+                wrap(&mut ranges, range, |range| width(range) > max_width, text);
+            } else {
+                ranges.push(range);
+            }
+        }
+
+        // shorten the last line which fits, or lines which are too wide,
+        // leaving out the ellipsis when even it does not fit
+        let ellipsis = if faces.iter().any(|face| face.glyph('…').id().0 != 0) {
+            "…"
+        } else {
+            "..."
+        };
+        let ellipsis_width = measure_chain(&faces, size, ellipsis).x;
+        let (ellipsis, ellipsis_width) = if ellipsis_width <= max_width {
+            (ellipsis, ellipsis_width)
+        } else {
+            ("", 0.0)
+        };
+        let mut truncated = vec![false; ranges.len()];
+        if options.ellipsis {
+            let fits = ((bounds.height() / line_height).floor() as usize).max(1);
+            if ranges.len() > fits {
+                ranges.truncate(fits);
+                truncated.truncate(fits);
+                truncated[fits - 1] = true;
+            }
+
+            for (range, truncated) in ranges.iter_mut().zip(truncated.iter_mut()) {
+                if !*truncated && width(range) <= max_width {
+                    continue;
+                }
+                *truncated = true;
+                let room = max_width - ellipsis_width;
+                range.end = fit(&faces, size, text, range.clone(), room);
+            }
+        }
+
+        let total_height = ranges.len() as f32 * line_height;
+        let mut y = match options.v_align {
+            VAlign::Top => bounds.min.y,
+            VAlign::Center => bounds.min.y + (bounds.height() - total_height) / 2.0,
+            VAlign::Bottom => bounds.max.y - total_height,
+        };
+
+        let mut block = TextBlock {
+            size,
+            ..TextBlock::default()
+        };
+        for (range, truncated) in ranges.into_iter().zip(truncated) {
+            let line = &text[range.clone()];
+            let shown = if truncated {
+                format!("{}{}", line, ellipsis)
+            } else {
+                line.to_owned()
+            };
+            let line_width = measure_chain(&faces, size, &shown).x;
+            let x = match options.h_align {
+                HAlign::Left => bounds.min.x,
+                HAlign::Center => bounds.min.x + (bounds.width() - line_width) / 2.0,
+                HAlign::Right => bounds.max.x - line_width,
+            };
+
+            // the ellipsis takes the index of the first character it hides
+            let indicies = line
+                .char_indices()
+                .map(|(i, _)| range.start + i)
+                .chain(ellipsis.chars().map(|_| range.end));
+
+            let first = block.glyphs.len();
+            let mut baseline = y;
+            let layout = GlyphLayout::new(&faces, size, Vec2::new(x, y), &shown);
+            for (laid, index) in layout.zip(indicies) {
+                baseline = laid.origin.y;
+                block.glyphs.push(BlockGlyph {
+                    font: ids[laid.font],
+                    id: laid.id,
+                    index,
+                    origin: laid.origin,
+                    rect: Rect::new(
+                        Vec2::new(laid.origin.x, y),
+                        Vec2::new(laid.origin.x + laid.advance, y + line_height),
+                    ),
+                });
+            }
+
+            block.lines.push(BlockLine {
+                range,
+                glyphs: first..block.glyphs.len(),
+                rect: Rect::new(Vec2::new(x, y), Vec2::new(x + line_width, y + line_height)),
+                baseline,
+            });
+            y += line_height;
+        }
+
+        block
+    }
+
+    /// Byte offset of the character under a point
+    pub fn char_at(&self, pos: Vec2) -> Option<usize> {
+        self.glyphs
+            .iter()
+            .find(|glyph| glyph.rect.contains(pos))
+            .map(|glyph| glyph.index)
+    }
+}
+
+/// Break a paragraph into lines which are not too wide
+///
+/// Lines break after the whitespace between words, which is dropped; words
+/// which are too wide on their own break between characters.
+fn wrap<F>(lines: &mut Vec<Range<usize>>, paragraph: Range<usize>, too_wide: F, text: &str)
+where
+    F: Fn(&Range<usize>) -> bool,
+{
+    let mut start = paragraph.start;
+    // end of the last word on the line and the start of the next word
+    let mut word_break: Option<(usize, usize)> = None;
+    let mut in_space = false;
+
+    for (i, c) in text[paragraph.clone()].char_indices() {
+        let i = paragraph.start + i;
+        if c.is_whitespace() {
+            match word_break.as_mut() {
+                Some((_, next)) if in_space => *next = i + c.len_utf8(),
+                _ => word_break = Some((i, i + c.len_utf8())),
+            }
+            in_space = true;
+            continue;
+        }
+        in_space = false;
+
+        if i == start || !too_wide(&(start..i + c.len_utf8())) {
+            continue;
+        }
+        match word_break.take() {
+            Some((end, next)) if end > start => {
+                lines.push(start..end);
+                start = next;
+            }
+            _ => {
+                lines.push(start..i);
+                start = i;
+            }
+        }
+
+        // the rest of the word may still be too wide for the new line
+        while start < i && too_wide(&(start..i + c.len_utf8())) {
+            let end = (start..i)
+                .rev()
+                .find(|&end| text.is_char_boundary(end) && !too_wide(&(start..end)))
+                .filter(|&end| end > start)
+                .unwrap_or(i);
+            lines.push(start..end);
+            start = end;
+        }
+    }
+
+    lines.push(start..paragraph.end);
+}
+
+/// End of the longest start of a line which fits within `room`, leaving out
+/// trailing whitespace
+fn fit(fonts: &[&Font<'static>], size: f32, text: &str, range: Range<usize>, room: f32) -> usize {
+    let line = &text[range.clone()];
+    let layout = GlyphLayout::new(fonts, size, Vec2::zero(), line);
+
+    let mut end = range.start;
+    for ((i, c), laid) in line.char_indices().zip(layout) {
+        if laid.origin.x + laid.advance > room {
+            break;
+        }
+        if !c.is_whitespace() {
+            end = range.start + i + c.len_utf8();
+        }
+    }
+    end
+}
+
+#[cfg(test)]
+mod test {
+    use super::{HAlign, TextBlock, TextOptions};
+    use crate::text::{test_fonts, FontFamily};
+    use crate::{Rect, Vec2};
+
+    fn lines<'a>(block: &TextBlock, text: &'a str) -> Vec<&'a str> {
+        block
+            .lines
+            .iter()
+            .map(|line| &text[line.range.clone()])
+            .collect()
+    }
+
+    #[test]
+    fn wraps_words_then_characters() {
+        let fonts = test_fonts();
+        let bounds = Rect::from_origin_size(Vec2::zero(), Vec2::new(50.0, 200.0));
+        let text = "a bb\nxxxxxxxxxxxxxxxx é";
+        let block = TextBlock::new(
+            &fonts,
+            FontFamily::PROPORTIONAL,
+            &TextOptions::default(),
+            bounds,
+            text,
+        );
+
+        let lines = lines(&block, text);
+        assert_eq!(lines[0], "a bb");
+        assert!(lines.len() > 3);
+        assert_eq!(lines.concat(), "a bbxxxxxxxxxxxxxxxxé");
+        for line in &block.lines {
+            assert!(line.rect.width() <= 50.0);
+        }
+    }
+
+    #[test]
+    fn ellipsis_and_alignment() {
+        let fonts = test_fonts();
+        let bounds = Rect::from_origin_size(Vec2::zero(), Vec2::new(80.0, 20.0));
+        let options = TextOptions {
+            h_align: HAlign::Right,
+            wrap: false,
+            ellipsis: true,
+            ..TextOptions::default()
+        };
+        let text = "a line which is much too long";
+        let block = TextBlock::new(&fonts, FontFamily::PROPORTIONAL, &options, bounds, text);
+
+        assert_eq!(block.lines.len(), 1);
+        let line = &block.lines[0];
+        assert!(line.range.end < text.len());
+        assert!(line.rect.width() <= 80.0);
+        assert!((line.rect.max.x - 80.0).abs() < 0.001);
+
+        let last = block.glyphs.last().unwrap();
+        assert_eq!(last.index, line.range.end);
+    }
+
+    #[test]
+    fn ellipsis_is_dropped_when_it_does_not_fit() {
+        let fonts = test_fonts();
+        let options = TextOptions {
+            wrap: false,
+            ellipsis: true,
+            ..TextOptions::default()
+        };
+        let text = "x".repeat(10_000);
+
+        // long lines are cut without measuring them over and over
+        let bounds = Rect::from_origin_size(Vec2::zero(), Vec2::new(100.0, 20.0));
+        let block = TextBlock::new(&fonts, FontFamily::PROPORTIONAL, &options, bounds, &text);
+        assert!(block.lines[0].rect.width() <= 100.0);
+
+        for width in [0.0, 2.0] {
+            let bounds = Rect::from_origin_size(Vec2::zero(), Vec2::new(width, 20.0));
+            let block = TextBlock::new(&fonts, FontFamily::PROPORTIONAL, &options, bounds, &text);
+            assert!(block.glyphs.is_empty());
+            assert_eq!(block.lines[0].rect.width(), 0.0);
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Signed distance fields generated from coverage textures
+
+use super::Texture;
+
+impl Texture {
+    /// Convert coverage into a signed distance field
+    ///
+    /// Texels at least half covered are inside.  Each texel of the result
+    /// stores the distance to the nearest edge, up to `spread` texels, mapped
+    /// so that `128` lies on the edge, `255` is `spread` texels inside and
+    /// `0` is `spread` texels outside.  Spreads below one texel are treated
+    /// as one texel.
+    pub fn to_distance_field(&self, spread: f32) -> Texture {
+        let spread = spread.max(1.0);
+        let (width, height) = (self.width as i32, self.height as i32);
+        let inside = |x: i32, y: i32| {
+            x >= 0 && y >= 0 && x < width && y < height && self[(x as u32, y as u32)] >= 128
+        };
+
+        let radius = spread.ceil() as i32;
+        let mut field = Texture::new(self.width, self.height);
+        for y in 0..height {
+            for x in 0..width {
+                let is_inside = inside(x, y);
+
+                // nearest texel on the other side of the edge
+                let mut nearest = None;
+                for dy in -radius..=radius {
+                    for dx in -radius..=radius {
+                        if inside(x + dx, y + dy) != is_inside {
+                            let d2 = (dx * dx + dy * dy) as f32;
+                            nearest = Some(nearest.map_or(d2, |n: f32| n.min(d2)));
+                        }
+                    }
+                }
+
+                // the edge lies halfway between texel centers
+                let distance = nearest.map_or(spread, |d2| (d2.sqrt() - 0.5).min(spread));
+                let signed = if is_inside { distance } else { -distance };
+                let value = 0.5 + signed / (2.0 * spread);
+                field[(x as u32, y as u32)] = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+            }
+        }
+
+        field
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Texture;
+
+    #[test]
+    fn distance_field_of_square() {
+        let mut texture = Texture::new(20, 20);
+        for y in 6..15 {
+            for x in 6..15 {
+                texture[(x, y)] = 255;
+            }
+        }
+
+        let field = texture.to_distance_field(4.0);
+        // far outside, on either side of the edge and deep inside
+        assert_eq!(field[(0, 0)], 0);
+        assert!(field[(5, 10)] < 128);
+        assert!(field[(6, 10)] > 128);
+        assert_eq!(field[(10, 10)], 255);
+
+        // a spread of nothing still has an edge
+        let unit = texture.to_distance_field(1.0);
+        assert_eq!(texture.to_distance_field(0.0).pixels(), unit.pixels());
+    }
+}